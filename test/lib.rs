@@ -1,9 +1,14 @@
 use cosmwasm_std::{
-    entry_point, to_json_binary, attr, Addr, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, 
-    Response, StdError, StdResult, Uint128
+    entry_point, to_json_binary, to_json_vec, attr, Addr, BankMsg, Binary, BlockInfo, Coin, CosmosMsg, Deps, DepsMut,
+    Empty, Env, Event, MessageInfo, Order, Reply, Response, StdError, StdResult, Storage, SubMsg,
+    SubMsgResult, Timestamp, Uint128
+};
+use cw_storage_plus::{
+    Bound, Index, IndexList, IndexedMap, Item, Map, MultiIndex, SnapshotItem, SnapshotMap, Strategy
 };
-use cw_storage_plus::{Item, Map};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct State {
@@ -11,8 +16,365 @@ pub struct State {
 }
 
 pub const STATE: Item<State> = Item::new("state");
-pub const BALANCES: Map<&Addr, Uint128> = Map::new("balances");
+/// Balances and their total are snapshotted at the checkpoints in `BALANCE_CHECKPOINTS`,
+/// which are added according to the configured snapshot interval.
+pub const BALANCES: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "balances",
+    "balance_checkpoints",
+    "balances__changelog",
+    Strategy::Selected,
+);
+pub const TOTAL_BALANCE: SnapshotItem<Uint128> = SnapshotItem::new(
+    "total_balance",
+    "total_balance_checkpoints",
+    "total_balance__changelog",
+    Strategy::Selected,
+);
+/// Heights checkpointed on both `BALANCES` and `TOTAL_BALANCE`.
+pub const BALANCE_CHECKPOINTS: Map<u64, Empty> = Map::new("balance_grid_checkpoints");
+/// Snapshot interval in blocks, keyed by the height it took effect at. Zero disables snapshots.
+pub const SNAPSHOT_INTERVALS: Map<u64, u64> = Map::new("snapshot_intervals");
 pub const TOKEN_DENOM: Item<String> = Item::new("token_denom");
+pub const REWARD_COUNT: Item<u64> = Item::new("reward_count");
+pub const MAX_BATCH_SIZE: Item<u32> = Item::new("max_batch_size");
+/// Client-supplied reward ids that were already credited, mapped to the contract's reward id.
+pub const PROCESSED_REWARD_IDS: Map<&str, u64> = Map::new("processed_reward_ids");
+/// Funds set aside for rewards that are committed now but credited later.
+pub const REWARD_POOL: Item<Uint128> = Item::new("reward_pool");
+pub const SCHEDULED_REWARD_COUNT: Item<u64> = Item::new("scheduled_reward_count");
+pub const STREAM_COUNT: Item<u64> = Item::new("stream_count");
+pub const CAMPAIGNS: Map<u64, Campaign> = Map::new("campaigns");
+pub const CAMPAIGN_COUNT: Item<u64> = Item::new("campaign_count");
+pub const DELEGATES: Map<&Addr, Delegate> = Map::new("delegates");
+pub const APPROVAL_CONFIG: Item<ApprovalConfig> = Item::new("approval_config");
+pub const PROPOSALS: Map<u64, RewardProposal> = Map::new("reward_proposals");
+pub const PROPOSAL_COUNT: Item<u64> = Item::new("reward_proposal_count");
+pub const FEE_CONFIG: Item<FeeConfig> = Item::new("fee_config");
+/// Seconds a withdrawal waits in the unbonding queue; zero pays out immediately.
+pub const UNBONDING_DELAY: Item<u64> = Item::new("unbonding_delay");
+pub const UNBONDING_COUNT: Item<u64> = Item::new("unbonding_count");
+pub const DUST_CONFIG: Item<DustConfig> = Item::new("dust_config");
+/// Time of the last balance change of each account, used to find inactive dust balances.
+pub const LAST_ACTIVE: Map<&Addr, Timestamp> = Map::new("last_active");
+/// Contract-wide default for sending rewards straight to the recipient's wallet.
+pub const AUTO_PAYOUT: Item<bool> = Item::new("auto_payout");
+/// Recipients' own choice of auto payout, which takes precedence over the reward and contract settings.
+pub const PAYOUT_PREFERENCES: Map<&Addr, bool> = Map::new("payout_preferences");
+/// Compressed or uncompressed secp256k1 public key whose signatures make reward vouchers valid.
+pub const VOUCHER_SIGNER: Item<Binary> = Item::new("voucher_signer");
+/// Voucher nonces that have been claimed, mapped to the reward id they were credited under.
+pub const USED_VOUCHER_NONCES: Map<&str, u64> = Map::new("used_voucher_nonces");
+pub const BLOCKED: Map<&Addr, BlockedAddress> = Map::new("blocked");
+pub const ELIGIBILITY_CONFIG: Item<EligibilityConfig> = Item::new("eligibility_config");
+/// Addresses added by the owner or proven against the eligibility Merkle root.
+pub const ELIGIBLE: Map<&Addr, Empty> = Map::new("eligible");
+pub const CAP_USAGE: Map<&Addr, CapUsage> = Map::new("cap_usage");
+/// Channels that `WithdrawIbc` may send over.
+pub const IBC_CHANNELS: Map<&str, Empty> = Map::new("ibc_channels");
+/// The IBC withdrawal awaiting its transfer sequence in `reply`.
+const PENDING_IBC_TRANSFER: Item<IbcTransfer> = Item::new("pending_ibc_transfer");
+/// In-flight IBC withdrawals keyed by channel and packet sequence until their lifecycle completes.
+pub const IBC_TRANSFERS: Map<(&str, u64), IbcTransfer> = Map::new("ibc_transfers");
+/// The withdrawal payout of the current execution, settled in `reply` once its bank send completes.
+const PENDING_PAYOUT: Item<PendingPayout> = Item::new("pending_payout");
+pub const FEE_GRANT_CONFIG: Item<FeeGrantConfig> = Item::new("fee_grant_config");
+/// Fee allowances issued to recipients; an address is only ever granted once.
+pub const FEE_GRANTS: Map<&Addr, FeeGrant> = Map::new("fee_grants");
+pub const FEE_GRANT_COUNT: Item<u64> = Item::new("fee_grant_count");
+/// Grantees of fee grant submessages awaiting their reply, keyed by grant id.
+const PENDING_FEE_GRANTS: Map<u64, Addr> = Map::new("pending_fee_grants");
+pub const REWARD_HISTORY: Map<u64, RewardRecord> = Map::new("reward_history");
+/// Operator tags accepted on rewards, mapped to the operator address allowed to use them.
+pub const OPERATOR_TAGS: Map<&str, Addr> = Map::new("operator_tags");
+pub const REASON_CODES: Map<&str, ReasonCode> = Map::new("reason_codes");
+pub const REASON_CODE_TOTALS: Map<&str, ReasonCodeTotals> = Map::new("reason_code_totals");
+pub const STATS: Item<Stats> = Item::new("stats");
+pub const ACCOUNT_STATS: Map<&Addr, AccountStats> = Map::new("account_stats");
+pub const REASON_TOTALS: Map<&str, Uint128> = Map::new("reason_totals");
+
+pub const DEFAULT_MAX_BATCH_SIZE: u32 = 100;
+/// Blocks between balance checkpoints until the owner configures an interval; roughly
+/// ten minutes at six-second blocks.
+pub const DEFAULT_SNAPSHOT_INTERVAL: u64 = 100;
+/// Hard cap on the withdrawal fee, in basis points of the withdrawn amount.
+pub const MAX_FEE_BPS: u16 = 1_000;
+const BPS_DENOMINATOR: u128 = 10_000;
+/// Maximum length of a free-form reward reason or memo.
+pub const MAX_REASON_LENGTH: usize = 256;
+pub const MAX_REASON_CODE_LENGTH: usize = 32;
+pub const DEFAULT_IBC_TIMEOUT_SECONDS: u64 = 600;
+pub const IBC_TRANSFER_REPLY_ID: u64 = 1;
+pub const WITHDRAW_PAYOUT_REPLY_ID: u64 = 2;
+/// Fee grant submessages reply with this offset plus the grant id.
+pub const FEE_GRANT_REPLY_ID_OFFSET: u64 = 1 << 32;
+pub const DEFAULT_DUST_INACTIVITY_SECONDS: u64 = 90 * 24 * 60 * 60;
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ScheduledReward {
+    pub id: u64,
+    pub recipient: Addr,
+    pub amount: Uint128,
+    pub reason: String,
+    pub release_at: Timestamp
+}
+
+pub struct ScheduledRewardIndexes<'a> {
+    pub recipient: MultiIndex<'a, Addr, ScheduledReward, u64>,
+}
+
+impl<'a> IndexList<ScheduledReward> for ScheduledRewardIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<ScheduledReward>> + '_> {
+        let v: Vec<&dyn Index<ScheduledReward>> = vec![&self.recipient];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn scheduled_rewards<'a>() -> IndexedMap<'a, u64, ScheduledReward, ScheduledRewardIndexes<'a>> {
+    let indexes = ScheduledRewardIndexes {
+        recipient: MultiIndex::new(
+            |_pk, reward| reward.recipient.clone(),
+            "scheduled_rewards",
+            "scheduled_rewards__recipient",
+        ),
+    };
+    IndexedMap::new("scheduled_rewards", indexes)
+}
+
+/// Withdrawals below `min_withdraw` are rejected unless they empty the balance. Balances below
+/// the sweep threshold that haven't changed for `inactivity_seconds` can be swept into the pool.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DustConfig {
+    pub min_withdraw: Uint128,
+    pub inactivity_seconds: u64
+}
+
+impl Default for DustConfig {
+    fn default() -> Self {
+        DustConfig {
+            min_withdraw: Uint128::zero(),
+            inactivity_seconds: DEFAULT_DUST_INACTIVITY_SECONDS,
+        }
+    }
+}
+
+/// A withdrawal that has left the balance and can be claimed once `release_at` has passed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct UnbondingEntry {
+    pub id: u64,
+    pub owner: Addr,
+    pub amount: Uint128,
+    pub release_at: Timestamp
+}
+
+pub struct UnbondingIndexes<'a> {
+    pub owner: MultiIndex<'a, Addr, UnbondingEntry, u64>,
+}
+
+impl<'a> IndexList<UnbondingEntry> for UnbondingIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<UnbondingEntry>> + '_> {
+        let v: Vec<&dyn Index<UnbondingEntry>> = vec![&self.owner];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn unbonding<'a>() -> IndexedMap<'a, u64, UnbondingEntry, UnbondingIndexes<'a>> {
+    let indexes = UnbondingIndexes {
+        owner: MultiIndex::new(
+            |_pk, entry| entry.owner.clone(),
+            "unbonding",
+            "unbonding__owner",
+        ),
+    };
+    IndexedMap::new("unbonding", indexes)
+}
+
+/// A reward paid out continuously at `rate_per_second` between `start` and `end`.
+/// `deposit` is reserved from the reward pool up front; `streamed` is what has been
+/// credited to the recipient so far.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Stream {
+    pub id: u64,
+    pub recipient: Addr,
+    pub rate_per_second: Uint128,
+    pub start: Timestamp,
+    pub end: Timestamp,
+    pub reason: String,
+    pub deposit: Uint128,
+    pub streamed: Uint128,
+    pub paused_at: Option<Timestamp>
+}
+
+impl Stream {
+    /// Total amount earned by `now`, frozen while the stream is paused.
+    fn accrued(&self, now: Timestamp) -> StdResult<Uint128> {
+        let until = self.paused_at.unwrap_or(now).min(self.end);
+        if until <= self.start {
+            return Ok(Uint128::zero());
+        }
+        let elapsed = until.seconds() - self.start.seconds();
+        Ok(self.rate_per_second.checked_mul(Uint128::from(elapsed))?.min(self.deposit))
+    }
+}
+
+/// A named group of rewards with its own budget and the managers allowed to spend it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Campaign {
+    pub id: u64,
+    pub name: String,
+    pub budget: Uint128,
+    pub denom: String,
+    pub start: Timestamp,
+    pub end: Timestamp,
+    pub managers: Vec<Addr>,
+    pub spent: Uint128
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CampaignResponse {
+    pub campaign: Campaign,
+    pub remaining: Uint128
+}
+
+/// A sub-admin allowed to issue up to `quota` in rewards every `period_seconds`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Delegate {
+    pub address: Addr,
+    pub quota: Uint128,
+    pub period_seconds: u64,
+    pub expires_at: Option<Timestamp>,
+    pub reason_prefix: Option<String>,
+    pub period_start: Timestamp,
+    pub used: Uint128
+}
+
+impl Delegate {
+    /// Starts a fresh quota period once the current one has elapsed.
+    fn roll_period(&mut self, now: Timestamp) {
+        if now.seconds() >= self.period_start.seconds().saturating_add(self.period_seconds) {
+            self.period_start = now;
+            self.used = Uint128::zero();
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DelegateResponse {
+    pub delegate: Delegate,
+    pub remaining_quota: Uint128
+}
+
+/// Rewards above `amount_threshold` need `threshold` approvals from `approvers` before they are paid.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ApprovalConfig {
+    pub approvers: Vec<Addr>,
+    pub threshold: u32,
+    pub amount_threshold: Uint128,
+    pub proposal_ttl_seconds: u64
+}
+
+/// Withdrawal fee of `fee_bps` basis points, at least `min_fee`, sent to `collector`.
+/// The fee is taken out of the withdrawn amount and never exceeds `MAX_FEE_BPS` of it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FeeConfig {
+    pub fee_bps: u16,
+    pub min_fee: Uint128,
+    pub collector: Addr
+}
+
+impl FeeConfig {
+    pub fn fee_for(&self, amount: Uint128) -> Uint128 {
+        let fee = amount
+            .multiply_ratio(self.fee_bps, BPS_DENOMINATOR)
+            .max(self.min_fee);
+        fee.min(amount.multiply_ratio(MAX_FEE_BPS, BPS_DENOMINATOR))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum ProposalStatus {
+    Pending,
+    Executed,
+    /// Reached its threshold while the recipient was blocked.
+    Rejected
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RewardProposal {
+    pub id: u64,
+    pub recipient: Addr,
+    pub amount: Uint128,
+    pub reason: String,
+    pub proposer: Addr,
+    pub approvals: Vec<Addr>,
+    pub expires_at: Timestamp,
+    pub status: ProposalStatus
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BalanceAtResponse {
+    /// Checkpoint height the value was read at; the requested height rounded down to the interval.
+    pub height: u64,
+    pub amount: Uint128
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StreamResponse {
+    pub stream: Stream,
+    pub withdrawable: Uint128
+}
+
+pub struct StreamIndexes<'a> {
+    pub recipient: MultiIndex<'a, Addr, Stream, u64>,
+}
+
+impl<'a> IndexList<Stream> for StreamIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Stream>> + '_> {
+        let v: Vec<&dyn Index<Stream>> = vec![&self.recipient];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn streams<'a>() -> IndexedMap<'a, u64, Stream, StreamIndexes<'a>> {
+    let indexes = StreamIndexes {
+        recipient: MultiIndex::new(|_pk, stream| stream.recipient.clone(), "streams", "streams__recipient"),
+    };
+    IndexedMap::new("streams", indexes)
+}
+
+/// Campaign id under which lifetime totals across all rewards are kept on the leaderboard.
+pub const GLOBAL_LEADERBOARD: u64 = 0;
+
+/// Lifetime rewards received by `recipient`, overall or within one campaign.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RecipientTotal {
+    pub campaign_id: u64,
+    pub recipient: Addr,
+    pub amount: Uint128
+}
+
+pub struct RecipientTotalIndexes<'a> {
+    pub amount: MultiIndex<'a, (u64, u128), RecipientTotal, (u64, Addr)>,
+}
+
+impl<'a> IndexList<RecipientTotal> for RecipientTotalIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<RecipientTotal>> + '_> {
+        let v: Vec<&dyn Index<RecipientTotal>> = vec![&self.amount];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn recipient_totals<'a>() -> IndexedMap<'a, (u64, &'a Addr), RecipientTotal, RecipientTotalIndexes<'a>> {
+    let indexes = RecipientTotalIndexes {
+        amount: MultiIndex::new(
+            |_pk, total| (total.campaign_id, total.amount.u128()),
+            "recipient_totals",
+            "recipient_totals__amount",
+        ),
+    };
+    IndexedMap::new("recipient_totals", indexes)
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct InstantiateMsg {
@@ -25,12 +387,29 @@ pub enum ExecuteMsg {
     Reward {
         recipient: String,
         amount: Uint128,
-        reason: String
+        reason: String,
+        reward_id: Option<String>,
+        campaign_id: Option<u64>,
+        auto_payout: Option<bool>,
+        operator_tag: Option<String>,
+        reason_code: Option<String>,
+        memo: Option<String>
     },
     RewardBulk {
         recipients: Vec<String>,
         amounts: Vec<Uint128>,
-        reasons: Vec<String>
+        reasons: Vec<String>,
+        reward_ids: Option<Vec<String>>,
+        #[serde(default)]
+        skip_invalid: bool,
+        auto_payout: Option<bool>,
+        operator_tag: Option<String>
+    },
+    RewardBatch {
+        entries: Vec<RewardEntry>,
+        #[serde(default)]
+        skip_invalid: bool,
+        operator_tag: Option<String>
     },
     Withdraw {
         amount: Uint128
@@ -41,12 +420,425 @@ pub enum ExecuteMsg {
     SetTokenDenom {
         denom: String
     },
+    SetMaxBatchSize {
+        max_batch_size: u32
+    },
+    FundPool {},
+    ScheduleReward {
+        recipient: String,
+        amount: Uint128,
+        reason: String,
+        release_at: Timestamp
+    },
+    CancelScheduledReward {
+        id: u64
+    },
+    ReleaseScheduledRewards {
+        address: String
+    },
+    CreateStream {
+        recipient: String,
+        rate_per_second: Uint128,
+        start: Timestamp,
+        end: Timestamp,
+        reason: String
+    },
+    PauseStream {
+        id: u64
+    },
+    ResumeStream {
+        id: u64
+    },
+    CancelStream {
+        id: u64
+    },
+    CreateCampaign {
+        name: String,
+        budget: Uint128,
+        denom: String,
+        start: Timestamp,
+        end: Timestamp,
+        managers: Vec<String>
+    },
+    SetDelegate {
+        address: String,
+        quota: Uint128,
+        period_seconds: u64,
+        expires_at: Option<Timestamp>,
+        reason_prefix: Option<String>
+    },
+    RemoveDelegate {
+        address: String
+    },
+    SetApprovalConfig {
+        approvers: Vec<String>,
+        threshold: u32,
+        amount_threshold: Uint128,
+        proposal_ttl_seconds: u64
+    },
+    ProposeReward {
+        recipient: String,
+        amount: Uint128,
+        reason: String
+    },
+    ApproveReward {
+        id: u64
+    },
+    SetSnapshotInterval {
+        interval: u64
+    },
+    UpdateFeeConfig {
+        fee_bps: u16,
+        min_fee: Option<Uint128>,
+        collector: String
+    },
+    SetUnbondingDelay {
+        delay_seconds: u64
+    },
+    ClaimUnbonded {},
+    UpdateDustConfig {
+        min_withdraw: Uint128,
+        inactivity_seconds: u64
+    },
+    SweepDust {
+        threshold: Uint128,
+        start_after: Option<String>,
+        limit: Option<u32>
+    },
+    SetAutoPayout {
+        auto: bool
+    },
+    SetPayoutPreference {
+        auto: bool
+    },
+    SetVoucherSigner {
+        pubkey: Option<Binary>
+    },
+    ClaimVoucher {
+        voucher: RewardVoucher,
+        signature: Binary
+    },
+    BlockAddress {
+        address: String,
+        #[serde(default)]
+        freeze: bool
+    },
+    UnblockAddress {
+        address: String
+    },
+    SetEligibilityConfig {
+        allowlist_only: bool,
+        merkle_root: Option<Binary>,
+        lifetime_cap: Option<Uint128>,
+        period_cap: Option<Uint128>,
+        period_seconds: u64
+    },
+    AddEligible {
+        addresses: Vec<String>
+    },
+    RemoveEligible {
+        addresses: Vec<String>
+    },
+    ProveEligibility {
+        address: String,
+        proof: Vec<Binary>
+    },
+    SetIbcChannel {
+        channel_id: String,
+        allowed: bool
+    },
+    WithdrawIbc {
+        channel_id: String,
+        remote_address: String,
+        amount: Uint128,
+        timeout: Option<u64>
+    },
+    SettleIbcTransfer {
+        channel_id: String,
+        sequence: u64,
+        success: bool
+    },
+    SetFeeGrantConfig {
+        denom: String,
+        spend_limit: Uint128,
+        expiration_seconds: Option<u64>
+    },
+    FundFeeGrants {},
+    RegisterOperatorTag {
+        tag: String,
+        grantee: String
+    },
+    RemoveOperatorTag {
+        tag: String
+    },
+    RegisterReasonCode {
+        code: String,
+        description: String,
+        default_amount: Option<Uint128>,
+        max_amount: Option<Uint128>
+    },
+}
+
+/// Lifecycle callbacks for IBC transfers sent by this contract, as delivered by the ibc-hooks module.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum SudoMsg {
+    #[serde(rename = "ibc_lifecycle_complete")]
+    IBCLifecycleComplete(IBCLifecycleComplete)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum IBCLifecycleComplete {
+    #[serde(rename = "ibc_ack")]
+    IBCAck {
+        channel: String,
+        sequence: u64,
+        ack: String,
+        success: bool
+    },
+    #[serde(rename = "ibc_timeout")]
+    IBCTimeout {
+        channel: String,
+        sequence: u64
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RewardEntry {
+    pub recipient: String,
+    pub amount: Uint128,
+    pub reason: String,
+    pub id: Option<String>,
+    pub campaign_id: Option<u64>,
+    pub auto_payout: Option<bool>,
+    pub reason_code: Option<String>,
+    pub memo: Option<String>
+}
+
+/// A blocked address can't receive rewards or withdraw. Its balance and any rewards already
+/// committed to it stay held until it is unblocked; with `freeze` set the balance is also
+/// exempt from dust sweeps.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BlockedAddress {
+    pub address: Addr,
+    pub freeze: bool
+}
+
+/// Optional limits on who can be rewarded and how much. With `allowlist_only` set, only
+/// addresses in `ELIGIBLE` receive rewards; they are added by the owner or prove membership
+/// of `merkle_root`. Caps bound what each recipient receives in total and per period.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct EligibilityConfig {
+    pub allowlist_only: bool,
+    pub merkle_root: Option<Binary>,
+    pub lifetime_cap: Option<Uint128>,
+    pub period_cap: Option<Uint128>,
+    pub period_seconds: u64
+}
+
+/// What a recipient has received since caps were configured.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct CapUsage {
+    pub lifetime: Uint128,
+    pub period_start: Timestamp,
+    pub period: Uint128
+}
+
+impl CapUsage {
+    /// Starts a fresh cap period once the current one has elapsed.
+    fn roll_period(&mut self, now: Timestamp, period_seconds: u64) {
+        if now.seconds() >= self.period_start.seconds().saturating_add(period_seconds) {
+            self.period_start = now;
+            self.period = Uint128::zero();
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CapUsageResponse {
+    pub eligible: bool,
+    pub usage: CapUsage,
+    pub lifetime_remaining: Option<Uint128>,
+    pub period_remaining: Option<Uint128>
+}
+
+/// An IBC withdrawal of `amount` to `remote_address`. Its `fee` goes to the fee collector once
+/// the transfer is acknowledged; if it fails or times out both are restored to `sender`.
+/// Transfers whose outcome is never reported can be settled by the owner after `timeout`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct IbcTransfer {
+    pub sender: Addr,
+    pub channel_id: String,
+    pub remote_address: String,
+    pub amount: Uint128,
+    pub fee: Uint128,
+    pub timeout: Timestamp
+}
+
+/// Sponsors gas for new recipients: the first reward credited to an address issues a feegrant
+/// `BasicAllowance` of `spend_limit` from the contract, while `budget` lasts. The budget is
+/// funded in `denom` through `SetFeeGrantConfig` or `FundFeeGrants` and kept apart from the
+/// reward pool.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FeeGrantConfig {
+    pub denom: String,
+    pub spend_limit: Uint128,
+    pub expiration_seconds: Option<u64>,
+    pub budget: Uint128
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FeeGrant {
+    pub id: u64,
+    pub amount: Uint128,
+    pub granted_at: Timestamp,
+    pub expires_at: Option<Timestamp>
+}
+
+/// A credited reward. `operator_tag` identifies the backend operator that submitted it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RewardRecord {
+    pub id: u64,
+    pub recipient: Addr,
+    pub amount: Uint128,
+    pub reason: String,
+    pub campaign_id: Option<u64>,
+    pub operator_tag: Option<String>,
+    pub reason_code: Option<String>,
+    pub memo: Option<String>,
+    pub timestamp: Timestamp
+}
+
+/// A registered reward reason. Rewards referencing the code with a zero amount are paid
+/// `default_amount`, and no reward under it may exceed `max_amount`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReasonCode {
+    pub code: String,
+    pub description: String,
+    pub default_amount: Option<Uint128>,
+    pub max_amount: Option<Uint128>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct ReasonCodeTotals {
+    pub count: u64,
+    pub amount: Uint128
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReasonCodeResponse {
+    pub reason_code: ReasonCode,
+    pub totals: ReasonCodeTotals
+}
+
+/// Contract-wide ledger totals. `total_withdrawn` counts debited withdrawals, less any that
+/// failed and were credited back.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct Stats {
+    pub total_rewarded: Uint128,
+    pub reward_count: u64,
+    pub unique_recipients: u64,
+    pub total_deposited: Uint128,
+    pub total_withdrawn: Uint128
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct AccountStats {
+    pub total_received: Uint128,
+    pub reward_count: u64,
+    pub total_deposited: Uint128,
+    pub total_withdrawn: Uint128
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReasonTotalResponse {
+    pub reason: String,
+    pub amount: Uint128
+}
+
+/// A withdrawal being paid out: `amount` was debited and is sent less `fee`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PendingPayout {
+    pub recipient: Addr,
+    pub amount: Uint128,
+    pub fee: Uint128
+}
+
+/// A reward signed off-chain by the voucher signer. The signature covers the SHA-256 digest
+/// of the voucher's JSON encoding, with fields in the order declared here.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RewardVoucher {
+    pub recipient: String,
+    pub amount: Uint128,
+    pub reason: String,
+    pub nonce: String,
+    pub expires_at: Timestamp,
+    pub contract_address: String,
+    pub chain_id: String
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum RewardEntryStatus {
+    Credited,
+    Duplicate,
+    Invalid
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RewardEntryResult {
+    pub index: u32,
+    pub recipient: String,
+    pub status: RewardEntryStatus,
+    pub reward_id: Option<u64>,
+    pub error: Option<String>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RewardBatchResponse {
+    pub results: Vec<RewardEntryResult>
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum QueryMsg {
     GetBalance { address: String },
-    GetTokenDenom {}
+    GetTokenDenom {},
+    GetProcessedReward { reward_id: String },
+    GetMaxBatchSize {},
+    GetRewardPool {},
+    GetScheduledRewards { address: String, start_after: Option<u64>, limit: Option<u32> },
+    GetStreams { address: String, start_after: Option<u64>, limit: Option<u32> },
+    GetCampaign { id: u64 },
+    ListCampaigns { start_after: Option<u64>, limit: Option<u32> },
+    GetDelegate { address: String },
+    ListDelegates { start_after: Option<String>, limit: Option<u32> },
+    GetApprovalConfig {},
+    GetProposal { id: u64 },
+    ListProposals { start_after: Option<u64>, limit: Option<u32> },
+    GetBalanceAt { address: String, height: u64 },
+    GetTotalAt { height: u64 },
+    GetSnapshotInterval {},
+    GetFeeConfig {},
+    GetUnbondingDelay {},
+    GetUnbonding { address: String, start_after: Option<u64>, limit: Option<u32> },
+    GetDustConfig {},
+    GetAutoPayout {},
+    GetPayoutPreference { address: String },
+    GetVoucherSigner {},
+    GetVoucherNonce { nonce: String },
+    ListBlocked { start_after: Option<String>, limit: Option<u32> },
+    GetEligibilityConfig {},
+    GetCapUsage { address: String },
+    ListIbcChannels {},
+    GetIbcTransfer { channel_id: String, sequence: u64 },
+    GetFeeGrantConfig {},
+    GetFeeGrant { address: String },
+    GetReward { id: u64 },
+    ListRewards { start_after: Option<u64>, limit: Option<u32> },
+    ListOperatorTags {},
+    ListReasonCodes { start_after: Option<String>, limit: Option<u32> },
+    GetStats {},
+    GetAccountStats { address: String },
+    ListReasonTotals { start_after: Option<String>, limit: Option<u32> },
+    TopRecipients { limit: Option<u32>, campaign_id: Option<u64>, start_after: Option<String> }
 }
 
 fn validate_owner(deps: Deps, info: &MessageInfo) -> StdResult<()> {
@@ -57,6 +849,36 @@ fn validate_owner(deps: Deps, info: &MessageInfo) -> StdResult<()> {
     Ok(())
 }
 
+/// Writes `balance` for `addr`, keeping the total balance, the snapshot checkpoints and the
+/// account's last activity time in sync.
+fn save_balance(storage: &mut dyn Storage, block: &BlockInfo, addr: &Addr, balance: Uint128) -> StdResult<()> {
+    let height = block.height;
+    LAST_ACTIVE.save(storage, addr, &block.time)?;
+
+    let (since, interval) = snapshot_interval_at(storage, height)?;
+    let checkpoint = checkpoint_height(since, interval, height);
+    if !BALANCE_CHECKPOINTS.has(storage, checkpoint) {
+        BALANCES.add_checkpoint(storage, checkpoint)?;
+        TOTAL_BALANCE.add_checkpoint(storage, checkpoint)?;
+        BALANCE_CHECKPOINTS.save(storage, checkpoint, &Empty {})?;
+    }
+
+    let old_balance = BALANCES.may_load(storage, addr)?.unwrap_or_default();
+    let total = TOTAL_BALANCE
+        .may_load(storage)?
+        .unwrap_or_default()
+        .checked_sub(old_balance)?
+        .checked_add(balance)?;
+    TOTAL_BALANCE.save(storage, &total, height)?;
+    BALANCES.save(storage, addr, &balance, height)
+}
+
+fn next_reward_id(storage: &mut dyn Storage) -> StdResult<u64> {
+    let reward_id = REWARD_COUNT.may_load(storage)?.unwrap_or_default() + 1;
+    REWARD_COUNT.save(storage, &reward_id)?;
+    Ok(reward_id)
+}
+
 #[entry_point]
 pub fn instantiate(
     deps: DepsMut,
@@ -74,7 +896,10 @@ pub fn instantiate(
     Ok(Response::new()
         .add_attribute("action", "instantiate")
         .add_attribute("owner", info.sender.to_string())
-        .add_attribute("token_denom", msg.token_denom))
+        .add_attribute("token_denom", msg.token_denom.clone())
+        .add_event(Event::new("thrive_instantiate")
+            .add_attribute("owner", info.sender.to_string())
+            .add_attribute("token_denom", msg.token_denom)))
 }
 
 #[entry_point]
@@ -90,23 +915,247 @@ pub fn execute(
             recipient,
             amount,
             reason,
-        } => execute_reward(deps, info, recipient, amount, reason),
+            reward_id,
+            campaign_id,
+            auto_payout,
+            operator_tag,
+            reason_code,
+            memo,
+        } => {
+            let entry = RewardEntry {
+                recipient,
+                amount,
+                reason,
+                id: reward_id,
+                campaign_id,
+                auto_payout,
+                reason_code,
+                memo,
+            };
+            execute_reward(deps, env, info, entry, operator_tag)
+        }
         ExecuteMsg::RewardBulk {
             recipients,
             amounts,
             reasons,
-        } => execute_reward_bulk(deps, info, recipients, amounts, reasons),
-        ExecuteMsg::Withdraw { amount } => execute_withdraw(deps, info, amount),
+            reward_ids,
+            skip_invalid,
+            auto_payout,
+            operator_tag,
+        } => execute_reward_bulk(
+            deps, env, info, recipients, amounts, reasons, reward_ids, skip_invalid, auto_payout, operator_tag
+        ),
+        ExecuteMsg::RewardBatch {
+            entries,
+            skip_invalid,
+            operator_tag,
+        } => execute_reward_batch(deps, env, info, entries, skip_invalid, operator_tag),
+        ExecuteMsg::Withdraw { amount } => execute_withdraw(deps, env, info, amount),
         ExecuteMsg::UpdateOwnership { new_owner } => update_ownership(deps, info, new_owner),
-        ExecuteMsg::SetTokenDenom { denom } => set_token_denom(deps, info, denom)
+        ExecuteMsg::SetTokenDenom { denom } => set_token_denom(deps, info, denom),
+        ExecuteMsg::SetMaxBatchSize { max_batch_size } => set_max_batch_size(deps, info, max_batch_size),
+        ExecuteMsg::FundPool {} => execute_fund_pool(deps, info),
+        ExecuteMsg::ScheduleReward {
+            recipient,
+            amount,
+            reason,
+            release_at,
+        } => execute_schedule_reward(deps, env, info, recipient, amount, reason, release_at),
+        ExecuteMsg::CancelScheduledReward { id } => execute_cancel_scheduled_reward(deps, env, info, id),
+        ExecuteMsg::ReleaseScheduledRewards { address } => execute_release_scheduled_rewards(deps, env, address),
+        ExecuteMsg::CreateStream {
+            recipient,
+            rate_per_second,
+            start,
+            end,
+            reason,
+        } => execute_create_stream(deps, env, info, recipient, rate_per_second, start, end, reason),
+        ExecuteMsg::PauseStream { id } => execute_pause_stream(deps, env, info, id),
+        ExecuteMsg::ResumeStream { id } => execute_resume_stream(deps, env, info, id),
+        ExecuteMsg::CancelStream { id } => execute_cancel_stream(deps, env, info, id),
+        ExecuteMsg::CreateCampaign {
+            name,
+            budget,
+            denom,
+            start,
+            end,
+            managers,
+        } => execute_create_campaign(deps, info, name, budget, denom, start, end, managers),
+        ExecuteMsg::SetDelegate {
+            address,
+            quota,
+            period_seconds,
+            expires_at,
+            reason_prefix,
+        } => execute_set_delegate(deps, env, info, address, quota, period_seconds, expires_at, reason_prefix),
+        ExecuteMsg::RemoveDelegate { address } => execute_remove_delegate(deps, info, address),
+        ExecuteMsg::SetApprovalConfig {
+            approvers,
+            threshold,
+            amount_threshold,
+            proposal_ttl_seconds,
+        } => execute_set_approval_config(deps, info, approvers, threshold, amount_threshold, proposal_ttl_seconds),
+        ExecuteMsg::ProposeReward {
+            recipient,
+            amount,
+            reason,
+        } => execute_propose_reward(deps, env, info, recipient, amount, reason),
+        ExecuteMsg::ApproveReward { id } => execute_approve_reward(deps, env, info, id),
+        ExecuteMsg::SetSnapshotInterval { interval } => execute_set_snapshot_interval(deps, env, info, interval),
+        ExecuteMsg::UpdateFeeConfig {
+            fee_bps,
+            min_fee,
+            collector,
+        } => execute_update_fee_config(deps, info, fee_bps, min_fee, collector),
+        ExecuteMsg::SetUnbondingDelay { delay_seconds } => execute_set_unbonding_delay(deps, info, delay_seconds),
+        ExecuteMsg::ClaimUnbonded {} => execute_claim_unbonded(deps, env, info),
+        ExecuteMsg::UpdateDustConfig {
+            min_withdraw,
+            inactivity_seconds,
+        } => execute_update_dust_config(deps, info, min_withdraw, inactivity_seconds),
+        ExecuteMsg::SweepDust {
+            threshold,
+            start_after,
+            limit,
+        } => execute_sweep_dust(deps, env, info, threshold, start_after, limit),
+        ExecuteMsg::SetAutoPayout { auto } => execute_set_auto_payout(deps, info, auto),
+        ExecuteMsg::SetPayoutPreference { auto } => execute_set_payout_preference(deps, info, auto),
+        ExecuteMsg::SetVoucherSigner { pubkey } => execute_set_voucher_signer(deps, info, pubkey),
+        ExecuteMsg::ClaimVoucher { voucher, signature } => execute_claim_voucher(deps, env, voucher, signature),
+        ExecuteMsg::BlockAddress { address, freeze } => execute_block_address(deps, info, address, freeze),
+        ExecuteMsg::UnblockAddress { address } => execute_unblock_address(deps, info, address),
+        ExecuteMsg::SetEligibilityConfig {
+            allowlist_only,
+            merkle_root,
+            lifetime_cap,
+            period_cap,
+            period_seconds,
+        } => execute_set_eligibility_config(
+            deps, info, allowlist_only, merkle_root, lifetime_cap, period_cap, period_seconds
+        ),
+        ExecuteMsg::AddEligible { addresses } => execute_update_eligible(deps, info, addresses, true),
+        ExecuteMsg::RemoveEligible { addresses } => execute_update_eligible(deps, info, addresses, false),
+        ExecuteMsg::ProveEligibility { address, proof } => execute_prove_eligibility(deps, address, proof),
+        ExecuteMsg::SetIbcChannel { channel_id, allowed } => execute_set_ibc_channel(deps, info, channel_id, allowed),
+        ExecuteMsg::WithdrawIbc {
+            channel_id,
+            remote_address,
+            amount,
+            timeout,
+        } => execute_withdraw_ibc(deps, env, info, channel_id, remote_address, amount, timeout),
+        ExecuteMsg::SettleIbcTransfer {
+            channel_id,
+            sequence,
+            success,
+        } => execute_settle_ibc_transfer(deps, env, info, channel_id, sequence, success),
+        ExecuteMsg::SetFeeGrantConfig {
+            denom,
+            spend_limit,
+            expiration_seconds,
+        } => execute_set_fee_grant_config(deps, info, denom, spend_limit, expiration_seconds),
+        ExecuteMsg::FundFeeGrants {} => execute_fund_fee_grants(deps, info),
+        ExecuteMsg::RegisterOperatorTag { tag, grantee } => execute_register_operator_tag(deps, info, tag, grantee),
+        ExecuteMsg::RemoveOperatorTag { tag } => execute_remove_operator_tag(deps, info, tag),
+        ExecuteMsg::RegisterReasonCode {
+            code,
+            description,
+            default_amount,
+            max_amount,
+        } => execute_register_reason_code(deps, info, code, description, default_amount, max_amount)
+    }
+}
+
+#[entry_point]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+    match msg.id {
+        IBC_TRANSFER_REPLY_ID => reply_ibc_transfer(deps, env, msg.result),
+        WITHDRAW_PAYOUT_REPLY_ID => reply_withdraw_payout(deps, env, msg.result),
+        id if id > FEE_GRANT_REPLY_ID_OFFSET => reply_fee_grant(deps, id - FEE_GRANT_REPLY_ID_OFFSET, msg.result),
+        id => Err(StdError::generic_err(format!("Unknown reply id: {}", id))),
     }
 }
 
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> StdResult<Response> {
+    match msg {
+        SudoMsg::IBCLifecycleComplete(IBCLifecycleComplete::IBCAck { channel, sequence, success, .. }) => {
+            complete_ibc_transfer(deps, env, channel, sequence, success)
+        }
+        SudoMsg::IBCLifecycleComplete(IBCLifecycleComplete::IBCTimeout { channel, sequence }) => {
+            complete_ibc_transfer(deps, env, channel, sequence, false)
+        }
+    }
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetBalance { address } => to_json_binary(&query_balance(deps, address)?),
-        QueryMsg::GetTokenDenom {} => to_json_binary(&query_token_denom(deps)?)
+        QueryMsg::GetTokenDenom {} => to_json_binary(&query_token_denom(deps)?),
+        QueryMsg::GetProcessedReward { reward_id } => to_json_binary(&query_processed_reward(deps, reward_id)?),
+        QueryMsg::GetMaxBatchSize {} => to_json_binary(&query_max_batch_size(deps)?),
+        QueryMsg::GetRewardPool {} => to_json_binary(&query_reward_pool(deps)?),
+        QueryMsg::GetScheduledRewards { address, start_after, limit } => {
+            to_json_binary(&query_scheduled_rewards(deps, address, start_after, limit)?)
+        }
+        QueryMsg::GetStreams { address, start_after, limit } => {
+            to_json_binary(&query_streams(deps, env, address, start_after, limit)?)
+        }
+        QueryMsg::GetCampaign { id } => to_json_binary(&query_campaign(deps, id)?),
+        QueryMsg::ListCampaigns { start_after, limit } => to_json_binary(&query_campaigns(deps, start_after, limit)?),
+        QueryMsg::GetDelegate { address } => to_json_binary(&query_delegate(deps, env, address)?),
+        QueryMsg::ListDelegates { start_after, limit } => {
+            to_json_binary(&query_delegates(deps, env, start_after, limit)?)
+        }
+        QueryMsg::GetApprovalConfig {} => to_json_binary(&APPROVAL_CONFIG.may_load(deps.storage)?),
+        QueryMsg::GetProposal { id } => to_json_binary(&load_proposal(deps.storage, id)?),
+        QueryMsg::ListProposals { start_after, limit } => to_json_binary(&query_proposals(deps, start_after, limit)?),
+        QueryMsg::GetBalanceAt { address, height } => to_json_binary(&query_balance_at(deps, env, address, height)?),
+        QueryMsg::GetTotalAt { height } => to_json_binary(&query_total_at(deps, env, height)?),
+        QueryMsg::GetSnapshotInterval {} => to_json_binary(&snapshot_interval_at(deps.storage, u64::MAX)?.1),
+        QueryMsg::GetFeeConfig {} => to_json_binary(&FEE_CONFIG.may_load(deps.storage)?),
+        QueryMsg::GetUnbondingDelay {} => to_json_binary(&UNBONDING_DELAY.may_load(deps.storage)?.unwrap_or_default()),
+        QueryMsg::GetUnbonding { address, start_after, limit } => {
+            to_json_binary(&query_unbonding(deps, address, start_after, limit)?)
+        }
+        QueryMsg::GetDustConfig {} => to_json_binary(&DUST_CONFIG.may_load(deps.storage)?.unwrap_or_default()),
+        QueryMsg::GetAutoPayout {} => to_json_binary(&AUTO_PAYOUT.may_load(deps.storage)?.unwrap_or_default()),
+        QueryMsg::GetPayoutPreference { address } => {
+            let addr = deps.api.addr_validate(&address)?;
+            to_json_binary(&PAYOUT_PREFERENCES.may_load(deps.storage, &addr)?)
+        }
+        QueryMsg::GetVoucherSigner {} => to_json_binary(&VOUCHER_SIGNER.may_load(deps.storage)?),
+        QueryMsg::GetVoucherNonce { nonce } => to_json_binary(&USED_VOUCHER_NONCES.may_load(deps.storage, &nonce)?),
+        QueryMsg::ListBlocked { start_after, limit } => to_json_binary(&query_blocked(deps, start_after, limit)?),
+        QueryMsg::GetEligibilityConfig {} => {
+            to_json_binary(&ELIGIBILITY_CONFIG.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::GetCapUsage { address } => to_json_binary(&query_cap_usage(deps, env, address)?),
+        QueryMsg::ListIbcChannels {} => {
+            to_json_binary(&IBC_CHANNELS.keys(deps.storage, None, None, Order::Ascending).collect::<StdResult<Vec<_>>>()?)
+        }
+        QueryMsg::GetIbcTransfer { channel_id, sequence } => {
+            to_json_binary(&IBC_TRANSFERS.may_load(deps.storage, (&channel_id, sequence))?)
+        }
+        QueryMsg::GetFeeGrantConfig {} => to_json_binary(&FEE_GRANT_CONFIG.may_load(deps.storage)?),
+        QueryMsg::GetFeeGrant { address } => {
+            let addr = deps.api.addr_validate(&address)?;
+            to_json_binary(&FEE_GRANTS.may_load(deps.storage, &addr)?)
+        }
+        QueryMsg::GetReward { id } => to_json_binary(&REWARD_HISTORY.may_load(deps.storage, id)?),
+        QueryMsg::ListRewards { start_after, limit } => to_json_binary(&query_rewards(deps, start_after, limit)?),
+        QueryMsg::ListOperatorTags {} => to_json_binary(&query_operator_tags(deps)?),
+        QueryMsg::ListReasonCodes { start_after, limit } => {
+            to_json_binary(&query_reason_codes(deps, start_after, limit)?)
+        }
+        QueryMsg::GetStats {} => to_json_binary(&STATS.may_load(deps.storage)?.unwrap_or_default()),
+        QueryMsg::GetAccountStats { address } => to_json_binary(&query_account_stats(deps, address)?),
+        QueryMsg::ListReasonTotals { start_after, limit } => {
+            to_json_binary(&query_reason_totals(deps, start_after, limit)?)
+        }
+        QueryMsg::TopRecipients { limit, campaign_id, start_after } => {
+            to_json_binary(&query_top_recipients(deps, limit, campaign_id, start_after)?)
+        }
     }
 }
 
@@ -120,186 +1169,4499 @@ fn query_token_denom(deps: Deps) -> StdResult<String> {
     TOKEN_DENOM.load(deps.storage)
 }
 
-pub fn execute_deposit(
-    deps: DepsMut,
-    _env: Env,
-    info: MessageInfo
-) -> StdResult<Response> {
-    let denom = TOKEN_DENOM.load(deps.storage)?;
-    let amount = info
-        .funds
-        .iter()
-        .find(|coin| coin.denom == denom)
-        .map(|coin| coin.amount)
-        .unwrap_or(Uint128::zero());
+fn query_processed_reward(deps: Deps, reward_id: String) -> StdResult<Option<u64>> {
+    PROCESSED_REWARD_IDS.may_load(deps.storage, &reward_id)
+}
 
-    if amount.is_zero() {
-        return Err(StdError::generic_err("Deposit amount must be greater than zero"));
-    }
+fn query_max_batch_size(deps: Deps) -> StdResult<u32> {
+    Ok(MAX_BATCH_SIZE.may_load(deps.storage)?.unwrap_or(DEFAULT_MAX_BATCH_SIZE))
+}
 
-    let current_balance = BALANCES
-        .may_load(deps.storage, &info.sender)?
-        .unwrap_or(Uint128::zero());
-    BALANCES.save(deps.storage, &info.sender, &(current_balance + amount))?;
+fn query_reward_pool(deps: Deps) -> StdResult<Uint128> {
+    Ok(REWARD_POOL.may_load(deps.storage)?.unwrap_or_default())
+}
 
-    Ok(Response::new()
-        .add_attribute("action", "deposit")
-        .add_attribute("sender", info.sender.to_string())
-        .add_attribute("amount", amount))
+fn query_unbonding(
+    deps: Deps,
+    address: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<UnbondingEntry>> {
+    let addr = deps.api.addr_validate(&address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    unbonding()
+        .idx
+        .owner
+        .prefix(addr)
+        .range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, entry)| entry))
+        .collect()
 }
 
-fn reward_single(
-    deps: DepsMut,
-    recipient: String,
-    amount: Uint128,
-    _reason: String,
-) -> StdResult<()> {
-    let recipient_addr = deps.api.addr_validate(&recipient)?;
-    BALANCES.update(deps.storage, &recipient_addr, |balance: Option<Uint128>| -> StdResult<_> {
-        Ok(balance.unwrap_or_default() + amount)
-    })?;
-    Ok(())
+fn query_scheduled_rewards(
+    deps: Deps,
+    address: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<ScheduledReward>> {
+    let addr = deps.api.addr_validate(&address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    scheduled_rewards()
+        .idx
+        .recipient
+        .prefix(addr)
+        .range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, reward)| reward))
+        .collect()
 }
 
-pub fn execute_reward_bulk(
-    mut deps: DepsMut,
-    info: MessageInfo,
-    recipients: Vec<String>,
-    amounts: Vec<Uint128>,
-    reasons: Vec<String>,
-) -> StdResult<Response> {
-    validate_owner(deps.as_ref(), &info)?;
+fn query_streams(
+    deps: Deps,
+    env: Env,
+    address: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<StreamResponse>> {
+    let addr = deps.api.addr_validate(&address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    streams()
+        .idx
+        .recipient
+        .prefix(addr)
+        .range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, stream) = item?;
+            let withdrawable = stream.accrued(env.block.time)?.checked_sub(stream.streamed)?;
+            Ok(StreamResponse { stream, withdrawable })
+        })
+        .collect()
+}
 
-    if recipients.len() != amounts.len() || recipients.len() != reasons.len() {
-        return Err(cosmwasm_std::StdError::generic_err("Array lengths mismatch"));
-    }
+fn campaign_response(campaign: Campaign) -> CampaignResponse {
+    let remaining = campaign.budget.saturating_sub(campaign.spent);
+    CampaignResponse { campaign, remaining }
+}
+
+fn query_campaign(deps: Deps, id: u64) -> StdResult<CampaignResponse> {
+    Ok(campaign_response(load_campaign(deps.storage, id)?))
+}
+
+fn query_campaigns(deps: Deps, start_after: Option<u64>, limit: Option<u32>) -> StdResult<Vec<CampaignResponse>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    CAMPAIGNS
+        .range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, campaign)| campaign_response(campaign)))
+        .collect()
+}
+
+fn delegate_response(env: &Env, mut delegate: Delegate) -> DelegateResponse {
+    delegate.roll_period(env.block.time);
+    let remaining_quota = delegate.quota.saturating_sub(delegate.used);
+    DelegateResponse { delegate, remaining_quota }
+}
+
+fn query_delegate(deps: Deps, env: Env, address: String) -> StdResult<DelegateResponse> {
+    let addr = deps.api.addr_validate(&address)?;
+    let delegate = DELEGATES
+        .may_load(deps.storage, &addr)?
+        .ok_or_else(|| StdError::generic_err(format!("Delegate not found: {}", addr)))?;
+    Ok(delegate_response(&env, delegate))
+}
+
+fn query_delegates(
+    deps: Deps,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<DelegateResponse>> {
+    let start_after = start_after.map(|addr| deps.api.addr_validate(&addr)).transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    DELEGATES
+        .range(deps.storage, start_after.as_ref().map(Bound::exclusive), None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, delegate)| delegate_response(&env, delegate)))
+        .collect()
+}
+
+fn query_blocked(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<Vec<BlockedAddress>> {
+    let start_after = start_after.map(|addr| deps.api.addr_validate(&addr)).transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    BLOCKED
+        .range(deps.storage, start_after.as_ref().map(Bound::exclusive), None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, blocked)| blocked))
+        .collect()
+}
+
+fn query_cap_usage(deps: Deps, env: Env, address: String) -> StdResult<CapUsageResponse> {
+    let addr = deps.api.addr_validate(&address)?;
+    let config = ELIGIBILITY_CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    let mut usage = CAP_USAGE.may_load(deps.storage, &addr)?.unwrap_or_default();
+    usage.roll_period(env.block.time, config.period_seconds);
+
+    Ok(CapUsageResponse {
+        eligible: !config.allowlist_only || ELIGIBLE.has(deps.storage, &addr),
+        lifetime_remaining: config.lifetime_cap.map(|cap| cap.saturating_sub(usage.lifetime)),
+        period_remaining: config.period_cap.map(|cap| cap.saturating_sub(usage.period)),
+        usage,
+    })
+}
+
+fn query_rewards(deps: Deps, start_after: Option<u64>, limit: Option<u32>) -> StdResult<Vec<RewardRecord>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    REWARD_HISTORY
+        .range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, record)| record))
+        .collect()
+}
+
+fn query_operator_tags(deps: Deps) -> StdResult<Vec<(String, Addr)>> {
+    OPERATOR_TAGS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect()
+}
+
+fn query_account_stats(deps: Deps, address: String) -> StdResult<AccountStats> {
+    let address = deps.api.addr_validate(&address)?;
+    Ok(ACCOUNT_STATS.may_load(deps.storage, &address)?.unwrap_or_default())
+}
+
+fn query_reason_totals(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<ReasonTotalResponse>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    REASON_TOTALS
+        .range(deps.storage, start_after.as_deref().map(Bound::exclusive), None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(reason, amount)| ReasonTotalResponse { reason, amount }))
+        .collect()
+}
+
+/// Recipients ordered by lifetime rewards received, largest first, within a campaign or
+/// overall when `campaign_id` is `None` or 0. `start_after` is the last recipient of the
+/// previous page.
+fn query_top_recipients(
+    deps: Deps,
+    limit: Option<u32>,
+    campaign_id: Option<u64>,
+    start_after: Option<String>,
+) -> StdResult<Vec<RecipientTotal>> {
+    let campaign_id = campaign_id.unwrap_or(GLOBAL_LEADERBOARD);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let max = match start_after {
+        Some(address) => {
+            let address = deps.api.addr_validate(&address)?;
+            let total = recipient_totals().load(deps.storage, (campaign_id, &address))?;
+            Some(Bound::exclusive((total.amount.u128(), (campaign_id, address))))
+        }
+        None => None,
+    };
+    recipient_totals()
+        .idx
+        .amount
+        .sub_prefix(campaign_id)
+        .range(deps.storage, None, max, Order::Descending)
+        .take(limit)
+        .map(|item| item.map(|(_, total)| total))
+        .collect()
+}
 
-    for ((recipient, amount), reason) in recipients.iter().zip(amounts.iter()).zip(reasons.iter()) {
-        reward_single(deps.branch(), recipient.clone(), *amount, reason.clone())?;
+fn query_reason_codes(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<ReasonCodeResponse>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    REASON_CODES
+        .range(deps.storage, start_after.as_deref().map(Bound::exclusive), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (code, reason_code) = item?;
+            let totals = REASON_CODE_TOTALS.may_load(deps.storage, &code)?.unwrap_or_default();
+            Ok(ReasonCodeResponse { reason_code, totals })
+        })
+        .collect()
+}
+
+fn query_proposals(deps: Deps, start_after: Option<u64>, limit: Option<u32>) -> StdResult<Vec<RewardProposal>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    PROPOSALS
+        .range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, proposal)| proposal))
+        .collect()
+}
+
+/// Returns the snapshot interval in effect at `height` together with the height it took effect at.
+fn snapshot_interval_at(storage: &dyn Storage, height: u64) -> StdResult<(u64, u64)> {
+    Ok(SNAPSHOT_INTERVALS
+        .range(storage, None, Some(Bound::inclusive(height)), Order::Descending)
+        .next()
+        .transpose()?
+        .unwrap_or((0, DEFAULT_SNAPSHOT_INTERVAL)))
+}
+
+/// Rounds `height` down to the checkpoint grid that starts at `since`.
+/// While snapshots are disabled every height maps onto `since`.
+fn checkpoint_height(since: u64, interval: u64, height: u64) -> u64 {
+    if interval == 0 {
+        return since;
     }
+    since + (height - since) / interval * interval
+}
 
-    Ok(Response::new().add_attributes(vec![attr("action", "reward_bulk")]))
+/// Resolves a historical query to the checkpoint holding its value. Checkpoints are only added
+/// on the first ledger write at or after a grid point, so the first checkpoint at or after the
+/// grid point carries the same value; `None` means nothing changed since and the current value applies.
+fn resolve_snapshot(deps: Deps, env: &Env, height: u64) -> StdResult<(u64, Option<u64>)> {
+    if height > env.block.height {
+        return Err(StdError::generic_err("Height is in the future"));
+    }
+    let (since, interval) = snapshot_interval_at(deps.storage, height)?;
+    if interval == 0 {
+        return Err(StdError::generic_err(format!("Balance snapshots were disabled at height {}", height)));
+    }
+    let grid_height = checkpoint_height(since, interval, height);
+    let checkpoint = BALANCE_CHECKPOINTS
+        .keys(deps.storage, Some(Bound::inclusive(grid_height)), None, Order::Ascending)
+        .next()
+        .transpose()?;
+    Ok((grid_height, checkpoint))
 }
 
-pub fn execute_reward(
-    deps: DepsMut,
-    info: MessageInfo,
-    recipient: String,
-    amount: Uint128,
-    reason: String,
-) -> StdResult<Response> {
-    validate_owner(deps.as_ref(), &info)?;
+fn query_balance_at(deps: Deps, env: Env, address: String, height: u64) -> StdResult<BalanceAtResponse> {
+    let addr = deps.api.addr_validate(&address)?;
+    let (grid_height, checkpoint) = resolve_snapshot(deps, &env, height)?;
+    let amount = match checkpoint {
+        Some(checkpoint) => BALANCES.may_load_at_height(deps.storage, &addr, checkpoint)?,
+        None => BALANCES.may_load(deps.storage, &addr)?,
+    };
+    Ok(BalanceAtResponse { height: grid_height, amount: amount.unwrap_or_default() })
+}
 
-    reward_single(deps, recipient.clone(), amount, reason.clone())?;
+fn query_total_at(deps: Deps, env: Env, height: u64) -> StdResult<BalanceAtResponse> {
+    let (grid_height, checkpoint) = resolve_snapshot(deps, &env, height)?;
+    let amount = match checkpoint {
+        Some(checkpoint) => TOTAL_BALANCE.may_load_at_height(deps.storage, checkpoint)?,
+        None => TOTAL_BALANCE.may_load(deps.storage)?,
+    };
+    Ok(BalanceAtResponse { height: grid_height, amount: amount.unwrap_or_default() })
+}
 
-    Ok(Response::new().add_attributes(vec![
-        attr("action", "reward"),
-        attr("recipient", recipient),
-        attr("amount", amount.to_string()),
-        attr("reason", reason),
-    ]))
+/// Returns the amount of `denom` attached to the message, or zero if none was sent.
+fn sent_amount(info: &MessageInfo, denom: &str) -> Uint128 {
+    info.funds
+        .iter()
+        .find(|coin| coin.denom == denom)
+        .map(|coin| coin.amount)
+        .unwrap_or(Uint128::zero())
 }
 
-pub fn execute_withdraw(
+pub fn execute_deposit(
     deps: DepsMut,
-    info: MessageInfo,
-    amount: Uint128,
+    env: Env,
+    info: MessageInfo
 ) -> StdResult<Response> {
+    let denom = TOKEN_DENOM.load(deps.storage)?;
+    let amount = sent_amount(&info, &denom);
+
     if amount.is_zero() {
-        return Err(StdError::generic_err("Withdraw amount must be greater than zero"));
+        return Err(StdError::generic_err("Deposit amount must be greater than zero"));
     }
 
-    let denom = TOKEN_DENOM.load(deps.storage)?;
     let current_balance = BALANCES
         .may_load(deps.storage, &info.sender)?
         .unwrap_or(Uint128::zero());
+    let new_balance = current_balance.checked_add(amount)?;
+    save_balance(deps.storage, &env.block, &info.sender, new_balance)?;
+    record_deposit(deps.storage, &info.sender, amount)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "deposit")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("amount", amount)
+        .add_event(Event::new("thrive_deposit")
+            .add_attribute("sender", info.sender.to_string())
+            .add_attribute("amount", amount.to_string())
+            .add_attribute("balance", new_balance.to_string())))
+}
+
+/// Statistics and leaderboard totals with a reward added, computed before any of them is written.
+struct RewardStats {
+    stats: Stats,
+    account: AccountStats,
+    reason_total: Uint128,
+    recipient_totals: Vec<RecipientTotal>,
+}
+
+fn reward_stats(storage: &dyn Storage, reward: &PendingReward) -> StdResult<RewardStats> {
+    let mut account = ACCOUNT_STATS.may_load(storage, &reward.recipient)?.unwrap_or_default();
+    let mut stats = STATS.may_load(storage)?.unwrap_or_default();
+    if account.reward_count == 0 {
+        stats.unique_recipients += 1;
+    }
+    account.reward_count += 1;
+    account.total_received = account.total_received.checked_add(reward.amount)?;
+    stats.reward_count += 1;
+    stats.total_rewarded = stats.total_rewarded.checked_add(reward.amount)?;
+    let reason_total = REASON_TOTALS
+        .may_load(storage, &reward.reason)?
+        .unwrap_or_default()
+        .checked_add(reward.amount)?;
+
+    let mut recipient_totals_after = vec![];
+    for campaign_id in std::iter::once(GLOBAL_LEADERBOARD).chain(reward.campaign_id) {
+        let amount = match recipient_totals().may_load(storage, (campaign_id, &reward.recipient))? {
+            Some(total) => total.amount.checked_add(reward.amount)?,
+            None => reward.amount,
+        };
+        recipient_totals_after.push(RecipientTotal { campaign_id, recipient: reward.recipient.clone(), amount });
+    }
+    Ok(RewardStats { stats, account, reason_total, recipient_totals: recipient_totals_after })
+}
+
+fn save_reward_stats(storage: &mut dyn Storage, reward: &PendingReward, reward_stats: RewardStats) -> StdResult<()> {
+    REASON_TOTALS.save(storage, &reward.reason, &reward_stats.reason_total)?;
+    ACCOUNT_STATS.save(storage, &reward.recipient, &reward_stats.account)?;
+    for total in reward_stats.recipient_totals {
+        recipient_totals().save(storage, (total.campaign_id, &reward.recipient), &total)?;
+    }
+    STATS.save(storage, &reward_stats.stats)
+}
+
+fn record_deposit(storage: &mut dyn Storage, sender: &Addr, amount: Uint128) -> StdResult<()> {
+    let mut account = ACCOUNT_STATS.may_load(storage, sender)?.unwrap_or_default();
+    let mut stats = STATS.may_load(storage)?.unwrap_or_default();
+    account.total_deposited = account.total_deposited.checked_add(amount)?;
+    stats.total_deposited = stats.total_deposited.checked_add(amount)?;
+    ACCOUNT_STATS.save(storage, sender, &account)?;
+    STATS.save(storage, &stats)
+}
+
+/// Adds a debited withdrawal to the totals, or takes a failed one back out when `restored`.
+fn record_withdrawal(storage: &mut dyn Storage, sender: &Addr, amount: Uint128, restored: bool) -> StdResult<()> {
+    let mut account = ACCOUNT_STATS.may_load(storage, sender)?.unwrap_or_default();
+    let mut stats = STATS.may_load(storage)?.unwrap_or_default();
+    if restored {
+        account.total_withdrawn = account.total_withdrawn.checked_sub(amount)?;
+        stats.total_withdrawn = stats.total_withdrawn.checked_sub(amount)?;
+    } else {
+        account.total_withdrawn = account.total_withdrawn.checked_add(amount)?;
+        stats.total_withdrawn = stats.total_withdrawn.checked_add(amount)?;
+    }
+    ACCOUNT_STATS.save(storage, sender, &account)?;
+    STATS.save(storage, &stats)
+}
+
+/// Balances touched while crediting rewards and amounts to send straight to recipients,
+/// written back or paid out once per recipient by `save_credits`.
+#[derive(Default)]
+struct PendingCredits {
+    balances: BTreeMap<Addr, Uint128>,
+    payouts: BTreeMap<Addr, Uint128>,
+    /// Recipients receiving their first reward, who are eligible for a fee grant.
+    first_rewarded: BTreeSet<Addr>,
+}
+
+/// A reward whose recipient has been validated and which is ready to be credited.
+struct PendingReward {
+    recipient: Addr,
+    amount: Uint128,
+    reason: String,
+    client_reward_id: Option<String>,
+    campaign_id: Option<u64>,
+    auto_payout: bool,
+    operator_tag: Option<String>,
+    reason_code: Option<String>,
+    memo: Option<String>,
+    /// Set for scheduled and streamed rewards, whose eligibility was checked and whose cap
+    /// usage was reserved when they were committed.
+    prefunded: bool,
+}
+
+impl PendingReward {
+    fn new(recipient: Addr, amount: Uint128, reason: String) -> Self {
+        PendingReward {
+            recipient,
+            amount,
+            reason,
+            client_reward_id: None,
+            campaign_id: None,
+            auto_payout: false,
+            operator_tag: None,
+            reason_code: None,
+            memo: None,
+            prefunded: false,
+        }
+    }
+}
+
+/// Whether a reward to `recipient` is sent straight to their wallet. The recipient's own
+/// preference wins, then the option given with the reward, then the contract default.
+/// While an unbonding delay or a withdrawal fee is configured rewards are always credited,
+/// so that paying them out goes through `Withdraw`.
+fn resolve_auto_payout(storage: &dyn Storage, recipient: &Addr, requested: Option<bool>) -> StdResult<bool> {
+    if UNBONDING_DELAY.may_load(storage)?.unwrap_or_default() > 0 {
+        return Ok(false);
+    }
+    if FEE_CONFIG.may_load(storage)?.is_some_and(|config| config.fee_bps > 0 || !config.min_fee.is_zero()) {
+        return Ok(false);
+    }
+    if let Some(preference) = PAYOUT_PREFERENCES.may_load(storage, recipient)? {
+        return Ok(preference);
+    }
+    match requested {
+        Some(auto) => Ok(auto),
+        None => Ok(AUTO_PAYOUT.may_load(storage)?.unwrap_or_default()),
+    }
+}
+
+fn check_not_blocked(storage: &dyn Storage, addr: &Addr) -> StdResult<()> {
+    if BLOCKED.has(storage, addr) {
+        return Err(StdError::generic_err(format!("Address is blocked: {}", addr)));
+    }
+    Ok(())
+}
+
+fn check_eligible(storage: &dyn Storage, addr: &Addr) -> StdResult<()> {
+    let config = ELIGIBILITY_CONFIG.may_load(storage)?.unwrap_or_default();
+    if config.allowlist_only && !ELIGIBLE.has(storage, addr) {
+        return Err(StdError::generic_err(format!("Recipient is not eligible: {}", addr)));
+    }
+    Ok(())
+}
+
+/// Returns `recipient`'s cap usage including `amount`, or `None` when no caps are configured.
+fn add_cap_usage(
+    storage: &dyn Storage,
+    now: Timestamp,
+    recipient: &Addr,
+    amount: Uint128,
+) -> StdResult<Option<CapUsage>> {
+    let config = ELIGIBILITY_CONFIG.may_load(storage)?.unwrap_or_default();
+    if config.lifetime_cap.is_none() && config.period_cap.is_none() {
+        return Ok(None);
+    }
+
+    let mut usage = CAP_USAGE.may_load(storage, recipient)?.unwrap_or_default();
+    usage.roll_period(now, config.period_seconds);
+    usage.lifetime = usage.lifetime.checked_add(amount)?;
+    usage.period = usage.period.checked_add(amount)?;
+    if config.lifetime_cap.is_some_and(|cap| usage.lifetime > cap) {
+        return Err(StdError::generic_err(format!("Lifetime reward cap exceeded for {}", recipient)));
+    }
+    if config.period_cap.is_some_and(|cap| usage.period > cap) {
+        return Err(StdError::generic_err(format!("Period reward cap exceeded for {}", recipient)));
+    }
+    Ok(Some(usage))
+}
+
+/// Reserves `amount` of `recipient`'s caps for a scheduled reward or stream when it is committed.
+fn reserve_cap_usage(storage: &mut dyn Storage, now: Timestamp, recipient: &Addr, amount: Uint128) -> StdResult<()> {
+    if let Some(usage) = add_cap_usage(storage, now, recipient, amount)? {
+        CAP_USAGE.save(storage, recipient, &usage)?;
+    }
+    Ok(())
+}
+
+/// Gives back the lifetime cap reserved for a cancelled scheduled reward or stream. Period usage
+/// is left as is and clears when the period rolls over.
+fn release_cap_usage(storage: &mut dyn Storage, recipient: &Addr, amount: Uint128) -> StdResult<()> {
+    if let Some(mut usage) = CAP_USAGE.may_load(storage, recipient)? {
+        usage.lifetime = usage.lifetime.saturating_sub(amount);
+        CAP_USAGE.save(storage, recipient, &usage)?;
+    }
+    Ok(())
+}
+
+/// A zero amount on a reward referencing a reason code stands for the code's default amount.
+fn resolve_reward_amount(storage: &dyn Storage, reason_code: Option<&str>, amount: Uint128) -> StdResult<Uint128> {
+    if !amount.is_zero() {
+        return Ok(amount);
+    }
+    let default_amount = match reason_code {
+        Some(code) => REASON_CODES.may_load(storage, code)?.and_then(|code| code.default_amount),
+        None => None,
+    };
+    Ok(default_amount.unwrap_or(amount))
+}
+
+fn check_reason_length(reason: &str, memo: Option<&str>) -> StdResult<()> {
+    if reason.len() > MAX_REASON_LENGTH {
+        return Err(StdError::generic_err(format!("Reason exceeds maximum length of {}", MAX_REASON_LENGTH)));
+    }
+    if memo.is_some_and(|memo| memo.len() > MAX_REASON_LENGTH) {
+        return Err(StdError::generic_err(format!("Memo exceeds maximum length of {}", MAX_REASON_LENGTH)));
+    }
+    Ok(())
+}
+
+/// Checks the reward's reason and memo lengths and its reason code, returning the code's
+/// totals with the reward added. Prefunded rewards had their reason checked when they were
+/// committed, and must not fail to credit later.
+fn check_reason(storage: &dyn Storage, reward: &PendingReward) -> StdResult<Option<ReasonCodeTotals>> {
+    if !reward.prefunded {
+        check_reason_length(&reward.reason, reward.memo.as_deref())?;
+    }
+    let Some(code) = &reward.reason_code else {
+        return Ok(None);
+    };
+
+    let reason_code = REASON_CODES
+        .may_load(storage, code)?
+        .ok_or_else(|| StdError::generic_err(format!("Unknown reason code: {}", code)))?;
+    if reason_code.max_amount.is_some_and(|max| reward.amount > max) {
+        return Err(StdError::generic_err(format!("Reward amount exceeds maximum for reason code {}", code)));
+    }
+    let mut totals = REASON_CODE_TOTALS.may_load(storage, code)?.unwrap_or_default();
+    totals.count += 1;
+    totals.amount = totals.amount.checked_add(reward.amount)?;
+    Ok(Some(totals))
+}
+
+/// Only registered operator tags may be attached to rewards, and only by the tag's grantee.
+/// Operators reward as delegates signing for themselves: under authz `MsgExec` the sender is
+/// the granting owner, so the contract couldn't tell which grantee used a tag.
+fn check_operator_tag(storage: &dyn Storage, sender: &Addr, operator_tag: Option<&String>) -> StdResult<()> {
+    let Some(tag) = operator_tag else {
+        return Ok(());
+    };
+    let grantee = OPERATOR_TAGS
+        .may_load(storage, tag)?
+        .ok_or_else(|| StdError::generic_err(format!("Unknown operator tag: {}", tag)))?;
+    if *sender != grantee {
+        return Err(StdError::generic_err(format!("Unauthorized to use operator tag: {}", tag)));
+    }
+    Ok(())
+}
+
+/// Rejects direct payments above the approval threshold; those must go through `ProposeReward`.
+fn check_approval_threshold(storage: &dyn Storage, amount: Uint128) -> StdResult<()> {
+    if let Some(config) = APPROVAL_CONFIG.may_load(storage)? {
+        if amount > config.amount_threshold {
+            return Err(StdError::generic_err("Reward amount exceeds approval threshold, submit a proposal"));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the sender may pay `amount` for `reason`. Owners may reward anyone and campaign
+/// managers may reward against the campaigns they manage; delegates are bound by their expiry,
+/// reason prefix and quota. For delegates the returned record has the amount already consumed
+/// and must be saved once the reward has been credited.
+fn authorize_reward(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    campaign_id: Option<u64>,
+    amount: Uint128,
+    reason: &str,
+) -> StdResult<Option<Delegate>> {
+    check_approval_threshold(deps.storage, amount)?;
+
+    let state = STATE.load(deps.storage)?;
+    if info.sender == state.owner {
+        return Ok(None);
+    }
+    if let Some(campaign_id) = campaign_id {
+        if load_campaign(deps.storage, campaign_id)?.managers.contains(&info.sender) {
+            return Ok(None);
+        }
+    }
+
+    let mut delegate = match DELEGATES.may_load(deps.storage, &info.sender)? {
+        Some(delegate) => delegate,
+        None if campaign_id.is_some() => {
+            return Err(StdError::generic_err("Unauthorized: Only the owner or a campaign manager can reward"));
+        }
+        None => return Err(StdError::generic_err("Unauthorized: Only the owner can call this")),
+    };
+    if delegate.expires_at.is_some_and(|expires_at| env.block.time >= expires_at) {
+        return Err(StdError::generic_err("Delegate expired"));
+    }
+    if let Some(prefix) = &delegate.reason_prefix {
+        if !reason.starts_with(prefix.as_str()) {
+            return Err(StdError::generic_err(format!("Delegate may only reward reasons starting with {}", prefix)));
+        }
+    }
+    delegate.roll_period(env.block.time);
+    delegate.used = delegate.used.checked_add(amount)?;
+    if delegate.used > delegate.quota {
+        return Err(StdError::generic_err("Delegate quota exceeded"));
+    }
+    Ok(Some(delegate))
+}
+
+/// Checks up front that the sender of a batch may reward at all: the owner, an unexpired
+/// delegate, or a manager of one of the batch's campaigns. Each entry is still authorized
+/// against its campaign and the delegate's quota by `authorize_reward`.
+fn authorize_rewarder(deps: Deps, env: &Env, info: &MessageInfo, entries: &[RewardEntry]) -> StdResult<()> {
+    let state = STATE.load(deps.storage)?;
+    if info.sender == state.owner {
+        return Ok(());
+    }
+    if let Some(delegate) = DELEGATES.may_load(deps.storage, &info.sender)? {
+        if delegate.expires_at.is_some_and(|expires_at| env.block.time >= expires_at) {
+            return Err(StdError::generic_err("Delegate expired"));
+        }
+        return Ok(());
+    }
+    for campaign_id in entries.iter().filter_map(|entry| entry.campaign_id) {
+        if let Some(campaign) = CAMPAIGNS.may_load(deps.storage, campaign_id)? {
+            if campaign.managers.contains(&info.sender) {
+                return Ok(());
+            }
+        }
+    }
+    Err(StdError::generic_err("Unauthorized: Only the owner can call this"))
+}
+
+/// Credits a single reward into `credits` and returns its reward id with the `thrive_reward`
+/// event describing it. All checks run before anything is written, so a failed reward leaves
+/// no partial state behind. When a client reward id is given it is recorded so that retries
+/// can be detected.
+fn reward_single(
+    deps: DepsMut,
+    env: &Env,
+    credits: &mut PendingCredits,
+    reward: PendingReward,
+) -> StdResult<(u64, Event)> {
+    check_not_blocked(deps.storage, &reward.recipient)?;
+    let reason_code_totals = check_reason(deps.storage, &reward)?;
+    if !reward.prefunded {
+        check_eligible(deps.storage, &reward.recipient)?;
+    }
+    let cap_usage = if reward.prefunded {
+        None
+    } else {
+        add_cap_usage(deps.storage, env.block.time, &reward.recipient, reward.amount)?
+    };
+
+    let campaign = match reward.campaign_id {
+        Some(campaign_id) => {
+            let mut campaign = load_campaign(deps.storage, campaign_id)?;
+            if env.block.time < campaign.start || env.block.time >= campaign.end {
+                return Err(StdError::generic_err(format!("Campaign {} is not active", campaign_id)));
+            }
+            campaign.spent = campaign.spent.checked_add(reward.amount)?;
+            if campaign.spent > campaign.budget {
+                return Err(StdError::generic_err(format!("Campaign {} budget exceeded", campaign_id)));
+            }
+            Some(campaign)
+        }
+        None => None,
+    };
+
+    let balance = match credits.balances.get(&reward.recipient) {
+        Some(balance) => *balance,
+        None => BALANCES.may_load(deps.storage, &reward.recipient)?.unwrap_or_default(),
+    };
+    let (new_balance, paid) = if reward.auto_payout {
+        let paid = credits.payouts.get(&reward.recipient).copied().unwrap_or_default();
+        (balance, Some(paid.checked_add(reward.amount)?))
+    } else {
+        (balance.checked_add(reward.amount)?, None)
+    };
+    let reward_stats = reward_stats(deps.storage, &reward)?;
+
+    match paid {
+        Some(paid) => credits.payouts.insert(reward.recipient.clone(), paid),
+        None => credits.balances.insert(reward.recipient.clone(), new_balance),
+    };
+    if reward_stats.account.reward_count == 1 {
+        credits.first_rewarded.insert(reward.recipient.clone());
+    }
+    save_reward_stats(deps.storage, &reward, reward_stats)?;
+    let reward_id = next_reward_id(deps.storage)?;
+    REWARD_HISTORY.save(deps.storage, reward_id, &RewardRecord {
+        id: reward_id,
+        recipient: reward.recipient.clone(),
+        amount: reward.amount,
+        reason: reward.reason.clone(),
+        campaign_id: reward.campaign_id,
+        operator_tag: reward.operator_tag.clone(),
+        reason_code: reward.reason_code.clone(),
+        memo: reward.memo.clone(),
+        timestamp: env.block.time,
+    })?;
+
+    let mut event = Event::new("thrive_reward")
+        .add_attribute("reward_id", reward_id.to_string())
+        .add_attribute("recipient", reward.recipient.to_string())
+        .add_attribute("amount", reward.amount.to_string())
+        .add_attribute("reason", reward.reason)
+        .add_attribute("balance", new_balance.to_string());
+    if reward.auto_payout {
+        event = event.add_attribute("auto_payout", "true");
+    }
+    if let Some(operator_tag) = reward.operator_tag {
+        event = event.add_attribute("operator_tag", operator_tag);
+    }
+    if let (Some(code), Some(totals)) = (reward.reason_code, reason_code_totals) {
+        REASON_CODE_TOTALS.save(deps.storage, &code, &totals)?;
+        event = event.add_attribute("reason_code", code);
+    }
+    if let Some(memo) = reward.memo {
+        event = event.add_attribute("memo", memo);
+    }
+    if let Some(client_reward_id) = reward.client_reward_id {
+        PROCESSED_REWARD_IDS.save(deps.storage, &client_reward_id, &reward_id)?;
+        event = event.add_attribute("client_reward_id", client_reward_id);
+    }
+    if let Some(campaign) = campaign {
+        CAMPAIGNS.save(deps.storage, campaign.id, &campaign)?;
+        event = event.add_attribute("campaign_id", campaign.id.to_string());
+    }
+    if let Some(cap_usage) = cap_usage {
+        CAP_USAGE.save(deps.storage, &reward.recipient, &cap_usage)?;
+    }
+    Ok((reward_id, event))
+}
+
+/// Writes the credited balances and returns the bank messages for auto payouts.
+fn save_credits(storage: &mut dyn Storage, block: &BlockInfo, credits: PendingCredits) -> StdResult<Vec<CosmosMsg>> {
+    for (recipient, balance) in credits.balances {
+        save_balance(storage, block, &recipient, balance)?;
+    }
+    if credits.payouts.is_empty() {
+        return Ok(vec![]);
+    }
 
-    if amount > current_balance {
-        return Err(StdError::generic_err("Insufficient balance"));
+    let denom = TOKEN_DENOM.load(storage)?;
+    Ok(credits
+        .payouts
+        .into_iter()
+        .map(|(recipient, amount)| CosmosMsg::Bank(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![Coin { denom: denom.clone(), amount }]
+        }))
+        .collect())
+}
+
+/// Credits a batch of rewards, writing each distinct recipient's balance once.
+/// Entries whose client id was already processed are skipped and reported as duplicates.
+/// With `skip_invalid` set, entries that fail validation are reported instead of reverting the batch.
+fn reward_entries(
+    mut deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    entries: Vec<RewardEntry>,
+    skip_invalid: bool,
+    operator_tag: Option<String>,
+) -> StdResult<(Vec<SubMsg>, Vec<Event>, Vec<RewardEntryResult>)> {
+    authorize_rewarder(deps.as_ref(), env, info, &entries)?;
+    check_operator_tag(deps.storage, &info.sender, operator_tag.as_ref())?;
+    let max_batch_size = MAX_BATCH_SIZE.may_load(deps.storage)?.unwrap_or(DEFAULT_MAX_BATCH_SIZE);
+    if entries.len() > max_batch_size as usize {
+        return Err(StdError::generic_err(format!("Batch size exceeds maximum of {}", max_batch_size)));
+    }
+
+    let mut credits = PendingCredits::default();
+    let mut events = Vec::with_capacity(entries.len());
+    let mut results = Vec::with_capacity(entries.len());
+    for (index, entry) in entries.into_iter().enumerate() {
+        // Already credited ids are skipped rather than failing the batch, so a retried
+        // batch only pays the entries that did not land the first time.
+        if let Some(id) = &entry.id {
+            if let Some(reward_id) = PROCESSED_REWARD_IDS.may_load(deps.storage, id)? {
+                events.push(Event::new("thrive_reward_duplicate")
+                    .add_attribute("client_reward_id", id)
+                    .add_attribute("reward_id", reward_id.to_string())
+                    .add_attribute("recipient", &entry.recipient));
+                results.push(RewardEntryResult {
+                    index: index as u32,
+                    recipient: entry.recipient,
+                    status: RewardEntryStatus::Duplicate,
+                    reward_id: Some(reward_id),
+                    error: None,
+                });
+                continue;
+            }
+        }
+
+        let amount = resolve_reward_amount(deps.storage, entry.reason_code.as_deref(), entry.amount)?;
+        let delegate = authorize_reward(
+            deps.as_ref(), env, info, entry.campaign_id, amount, &entry.reason
+        )?;
+        let outcome = deps.api.addr_validate(&entry.recipient).and_then(|recipient| {
+            let auto_payout = resolve_auto_payout(deps.storage, &recipient, entry.auto_payout)?;
+            reward_single(deps.branch(), env, &mut credits, PendingReward {
+                client_reward_id: entry.id,
+                campaign_id: entry.campaign_id,
+                auto_payout,
+                operator_tag: operator_tag.clone(),
+                reason_code: entry.reason_code,
+                memo: entry.memo,
+                ..PendingReward::new(recipient, amount, entry.reason)
+            })
+        });
+        match outcome {
+            Ok((reward_id, event)) => {
+                if let Some(delegate) = delegate {
+                    DELEGATES.save(deps.storage, &info.sender, &delegate)?;
+                }
+                events.push(event);
+                results.push(RewardEntryResult {
+                    index: index as u32,
+                    recipient: entry.recipient,
+                    status: RewardEntryStatus::Credited,
+                    reward_id: Some(reward_id),
+                    error: None,
+                });
+            }
+            Err(err) if skip_invalid => {
+                events.push(Event::new("thrive_reward_invalid")
+                    .add_attribute("index", index.to_string())
+                    .add_attribute("recipient", &entry.recipient)
+                    .add_attribute("error", err.to_string()));
+                results.push(RewardEntryResult {
+                    index: index as u32,
+                    recipient: entry.recipient,
+                    status: RewardEntryStatus::Invalid,
+                    reward_id: None,
+                    error: Some(err.to_string()),
+                });
+            }
+            Err(err) => return Err(err),
+        }
     }
+    let grants = fee_grant_messages(deps.storage, env, credits.first_rewarded.iter())?;
+    let mut messages: Vec<SubMsg> = save_credits(deps.storage, &env.block, credits)?
+        .into_iter()
+        .map(SubMsg::new)
+        .collect();
+    messages.extend(grants);
+
+    Ok((messages, events, results))
+}
 
-    let bank_msg = CosmosMsg::Bank(BankMsg::Send {
-        to_address: info.sender.to_string(),
-        amount: vec![Coin { denom, amount }]
-    });
+#[allow(clippy::too_many_arguments)]
+pub fn execute_reward_bulk(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipients: Vec<String>,
+    amounts: Vec<Uint128>,
+    reasons: Vec<String>,
+    reward_ids: Option<Vec<String>>,
+    skip_invalid: bool,
+    auto_payout: Option<bool>,
+    operator_tag: Option<String>,
+) -> StdResult<Response> {
+    if recipients.len() != amounts.len() || recipients.len() != reasons.len() {
+        return Err(cosmwasm_std::StdError::generic_err("Array lengths mismatch"));
+    }
+    let reward_ids = match reward_ids {
+        Some(ids) if ids.len() != recipients.len() => {
+            return Err(StdError::generic_err("Array lengths mismatch"));
+        }
+        Some(ids) => ids.into_iter().map(Some).collect(),
+        None => vec![None; recipients.len()],
+    };
 
-    BALANCES.save(deps.storage, &info.sender, &(current_balance - amount))?;
+    let entries = recipients
+        .into_iter()
+        .zip(amounts)
+        .zip(reasons)
+        .zip(reward_ids)
+        .map(|(((recipient, amount), reason), id)| RewardEntry {
+            recipient,
+            amount,
+            reason,
+            id,
+            campaign_id: None,
+            auto_payout,
+            reason_code: None,
+            memo: None,
+        })
+        .collect();
+    let (messages, events, results) = reward_entries(deps, &env, &info, entries, skip_invalid, operator_tag)?;
 
     Ok(Response::new()
-        .add_message(bank_msg)
-        .add_attribute("action", "withdraw")
-        .add_attribute("amount", amount)
-        .add_event(cosmwasm_std::Event::new("Withdrawal")
-            .add_attribute("sender", info.sender.to_string())
-            .add_attribute("amount", amount.to_string())))
+        .add_submessages(messages)
+        .add_attributes(vec![attr("action", "reward_bulk")])
+        .add_events(events)
+        .set_data(to_json_binary(&RewardBatchResponse { results })?))
 }
 
-pub fn update_ownership(
+pub fn execute_reward_batch(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    new_owner: String,
+    entries: Vec<RewardEntry>,
+    skip_invalid: bool,
+    operator_tag: Option<String>,
 ) -> StdResult<Response> {
-    validate_owner(deps.as_ref(), &info)?;
+    let count = entries.len();
+    let (messages, events, results) = reward_entries(deps, &env, &info, entries, skip_invalid, operator_tag)?;
+
+    Ok(Response::new()
+        .add_submessages(messages)
+        .add_attributes(vec![attr("action", "reward_batch"), attr("count", count.to_string())])
+        .add_events(events)
+        .set_data(to_json_binary(&RewardBatchResponse { results })?))
+}
+
+pub fn execute_reward(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    entry: RewardEntry,
+    operator_tag: Option<String>,
+) -> StdResult<Response> {
+    let RewardEntry {
+        recipient,
+        amount,
+        reason,
+        id: reward_id,
+        campaign_id,
+        auto_payout,
+        reason_code,
+        memo,
+    } = entry;
+    let amount = resolve_reward_amount(deps.storage, reason_code.as_deref(), amount)?;
+    let delegate = authorize_reward(deps.as_ref(), &env, &info, campaign_id, amount, &reason)?;
+    check_operator_tag(deps.storage, &info.sender, operator_tag.as_ref())?;
+
+    if let Some(id) = &reward_id {
+        if PROCESSED_REWARD_IDS.has(deps.storage, id) {
+            return Err(StdError::generic_err(format!("Reward id already processed: {}", id)));
+        }
+    }
+
+    let recipient_addr = deps.api.addr_validate(&recipient)?;
+    let auto_payout = resolve_auto_payout(deps.storage, &recipient_addr, auto_payout)?;
+    let mut credits = PendingCredits::default();
+    let (_, event) = reward_single(deps.branch(), &env, &mut credits, PendingReward {
+        client_reward_id: reward_id,
+        campaign_id,
+        auto_payout,
+        operator_tag,
+        reason_code,
+        memo,
+        ..PendingReward::new(recipient_addr, amount, reason.clone())
+    })?;
+    let grants = fee_grant_messages(deps.storage, &env, credits.first_rewarded.iter())?;
+    let messages = save_credits(deps.storage, &env.block, credits)?;
+    if let Some(delegate) = delegate {
+        DELEGATES.save(deps.storage, &info.sender, &delegate)?;
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_submessages(grants)
+        .add_attributes(vec![
+            attr("action", "reward"),
+            attr("recipient", recipient),
+            attr("amount", amount.to_string()),
+            attr("reason", reason),
+        ])
+        .add_event(event))
+}
+
+pub fn execute_withdraw(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> StdResult<Response> {
+    let (new_balance, released) = debit_withdrawal(deps.branch(), &env, &info.sender, amount)?;
+    let denom = TOKEN_DENOM.load(deps.storage)?;
+
+    // `Withdrawal` predates the `thrive_*` events and is kept for existing consumers.
+    let response = Response::new()
+        .add_attribute("action", "withdraw")
+        .add_attribute("amount", amount)
+        .add_events(released)
+        .add_event(Event::new("Withdrawal")
+            .add_attribute("sender", info.sender.to_string())
+            .add_attribute("amount", amount.to_string()));
+
+    let delay = UNBONDING_DELAY.may_load(deps.storage)?.unwrap_or_default();
+    if delay > 0 {
+        let id = UNBONDING_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+        UNBONDING_COUNT.save(deps.storage, &id)?;
+        let release_at = env.block.time.plus_seconds(delay);
+        unbonding().save(deps.storage, id, &UnbondingEntry {
+            id,
+            owner: info.sender.clone(),
+            amount,
+            release_at,
+        })?;
+
+        return Ok(response
+            .add_event(Event::new("thrive_unbond")
+                .add_attribute("unbonding_id", id.to_string())
+                .add_attribute("sender", info.sender.to_string())
+                .add_attribute("amount", amount.to_string())
+                .add_attribute("release_at", release_at.to_string())
+                .add_attribute("balance", new_balance.to_string())));
+    }
+
+    let (message, fee, payout) = payout_message(deps.storage, &info.sender, denom, amount)?;
+    Ok(response
+        .add_submessage(message)
+        .add_event(Event::new("thrive_withdraw")
+            .add_attribute("sender", info.sender.to_string())
+            .add_attribute("amount", amount.to_string())
+            .add_attribute("fee", fee.to_string())
+            .add_attribute("payout", payout.to_string())
+            .add_attribute("balance", new_balance.to_string())))
+}
+
+/// Takes `amount` out of `sender`'s balance for a withdrawal, after crediting their matured
+/// scheduled rewards and streams. Returns the new balance and the events of those credits.
+fn debit_withdrawal(
+    mut deps: DepsMut,
+    env: &Env,
+    sender: &Addr,
+    amount: Uint128,
+) -> StdResult<(Uint128, Vec<Event>)> {
+    if amount.is_zero() {
+        return Err(StdError::generic_err("Withdraw amount must be greater than zero"));
+    }
+
+    check_not_blocked(deps.storage, sender)?;
+
+    let mut released = release_scheduled_rewards(deps.branch(), env, sender)?;
+    released.extend(settle_streams(deps.branch(), env, sender)?);
+
+    let current_balance = BALANCES
+        .may_load(deps.storage, sender)?
+        .unwrap_or(Uint128::zero());
+
+    let new_balance = current_balance
+        .checked_sub(amount)
+        .map_err(|_| StdError::generic_err("Insufficient balance"))?;
+
+    let min_withdraw = DUST_CONFIG.may_load(deps.storage)?.unwrap_or_default().min_withdraw;
+    if amount < min_withdraw && !new_balance.is_zero() {
+        return Err(StdError::generic_err(format!(
+            "Withdraw amount is below the minimum of {}", min_withdraw
+        )));
+    }
+
+    save_balance(deps.storage, &env.block, sender, new_balance)?;
+    record_withdrawal(deps.storage, sender, amount, false)?;
+    Ok((new_balance, released))
+}
+
+fn withdrawal_fee(storage: &dyn Storage, amount: Uint128) -> StdResult<Uint128> {
+    Ok(FEE_CONFIG.may_load(storage)?.map(|config| config.fee_for(amount)).unwrap_or_default())
+}
+
+/// Returns the bank message sending a collected withdrawal fee to the fee collector.
+fn fee_message(storage: &dyn Storage, fee: Uint128) -> StdResult<Option<CosmosMsg>> {
+    let Some(config) = FEE_CONFIG.may_load(storage)? else {
+        return Ok(None);
+    };
+    if fee.is_zero() {
+        return Ok(None);
+    }
+    Ok(Some(CosmosMsg::Bank(BankMsg::Send {
+        to_address: config.collector.to_string(),
+        amount: vec![Coin { denom: TOKEN_DENOM.load(storage)?, amount: fee }]
+    })))
+}
+
+/// Builds the submessage paying `amount` to `recipient`, minus the withdrawal fee. `reply`
+/// sends the fee to the fee collector once the payout succeeds, or credits the whole amount
+/// back if it fails. Returns the submessage with the fee and the recipient's payout.
+fn payout_message(
+    storage: &mut dyn Storage,
+    recipient: &Addr,
+    denom: String,
+    amount: Uint128,
+) -> StdResult<(SubMsg, Uint128, Uint128)> {
+    let fee = withdrawal_fee(storage, amount)?;
+    let payout = amount.checked_sub(fee)?;
+
+    PENDING_PAYOUT.save(storage, &PendingPayout { recipient: recipient.clone(), amount, fee })?;
+    let send = BankMsg::Send {
+        to_address: recipient.to_string(),
+        amount: vec![Coin { denom, amount: payout }]
+    };
+    Ok((SubMsg::reply_always(send, WITHDRAW_PAYOUT_REPLY_ID), fee, payout))
+}
+
+/// Credits a withdrawal that couldn't be delivered back to `recipient` and returns the new balance.
+fn restore_withdrawal(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    recipient: &Addr,
+    amount: Uint128,
+) -> StdResult<Uint128> {
+    let balance = BALANCES
+        .may_load(storage, recipient)?
+        .unwrap_or_default()
+        .checked_add(amount)?;
+    save_balance(storage, block, recipient, balance)?;
+    record_withdrawal(storage, recipient, amount, true)?;
+    Ok(balance)
+}
+
+fn reply_withdraw_payout(deps: DepsMut, env: Env, result: SubMsgResult) -> StdResult<Response> {
+    let payout = PENDING_PAYOUT.load(deps.storage)?;
+    PENDING_PAYOUT.remove(deps.storage);
+    let SubMsgResult::Err(error) = result else {
+        return Ok(Response::new().add_messages(fee_message(deps.storage, payout.fee)?));
+    };
+    let balance = restore_withdrawal(deps.storage, &env.block, &payout.recipient, payout.amount)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "withdraw_failed")
+        .add_event(Event::new("thrive_withdraw_failed")
+            .add_attribute("recipient", payout.recipient.to_string())
+            .add_attribute("amount", payout.amount.to_string())
+            .add_attribute("error", error)
+            .add_attribute("balance", balance.to_string())))
+}
+
+/// Withdraws to an address on another chain over an allow-listed channel. The transfer is
+/// tracked by its packet sequence so the balance can be restored if the packet fails or
+/// times out, as reported by ibc-hooks, or right away if the transfer message itself fails.
+/// Unbonding withdrawals can't be sent over IBC.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_withdraw_ibc(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    channel_id: String,
+    remote_address: String,
+    amount: Uint128,
+    timeout: Option<u64>,
+) -> StdResult<Response> {
+    if !IBC_CHANNELS.has(deps.storage, &channel_id) {
+        return Err(StdError::generic_err(format!("IBC channel not allowed: {}", channel_id)));
+    }
+    if remote_address.is_empty() {
+        return Err(StdError::generic_err("Remote address must not be empty"));
+    }
+    if UNBONDING_DELAY.may_load(deps.storage)?.unwrap_or_default() > 0 {
+        return Err(StdError::generic_err("IBC withdrawals are unavailable while an unbonding delay is set"));
+    }
+
+    let (new_balance, released) = debit_withdrawal(deps.branch(), &env, &info.sender, amount)?;
+    let denom = TOKEN_DENOM.load(deps.storage)?;
+    let fee = withdrawal_fee(deps.storage, amount)?;
+    let payout = amount.checked_sub(fee)?;
+
+    let timeout = env.block.time.plus_seconds(timeout.unwrap_or(DEFAULT_IBC_TIMEOUT_SECONDS));
+    PENDING_IBC_TRANSFER.save(deps.storage, &IbcTransfer {
+        sender: info.sender.clone(),
+        channel_id: channel_id.clone(),
+        remote_address: remote_address.clone(),
+        amount: payout,
+        fee,
+        timeout,
+    })?;
+    let transfer = CosmosMsg::Stargate {
+        type_url: "/ibc.applications.transfer.v1.MsgTransfer".to_string(),
+        value: encode_ibc_transfer(
+            &env.contract.address,
+            &channel_id,
+            &remote_address,
+            &Coin { denom, amount: payout },
+            timeout,
+        ),
+    };
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_always(transfer, IBC_TRANSFER_REPLY_ID))
+        .add_attribute("action", "withdraw_ibc")
+        .add_attribute("amount", amount)
+        .add_events(released)
+        .add_event(Event::new("thrive_withdraw_ibc")
+            .add_attribute("sender", info.sender.to_string())
+            .add_attribute("channel_id", channel_id)
+            .add_attribute("remote_address", remote_address)
+            .add_attribute("amount", amount.to_string())
+            .add_attribute("fee", fee.to_string())
+            .add_attribute("payout", payout.to_string())
+            .add_attribute("balance", new_balance.to_string())))
+}
+
+/// Files the pending IBC withdrawal under the packet sequence from the `MsgTransferResponse`,
+/// or credits it back if the transfer could not be sent.
+fn reply_ibc_transfer(deps: DepsMut, env: Env, result: SubMsgResult) -> StdResult<Response> {
+    let transfer = PENDING_IBC_TRANSFER.load(deps.storage)?;
+    PENDING_IBC_TRANSFER.remove(deps.storage);
+
+    let response = match result {
+        SubMsgResult::Ok(response) => response,
+        SubMsgResult::Err(error) => {
+            let amount = transfer.amount.checked_add(transfer.fee)?;
+            let balance = restore_withdrawal(deps.storage, &env.block, &transfer.sender, amount)?;
+            return Ok(Response::new()
+                .add_attribute("action", "withdraw_failed")
+                .add_event(Event::new("thrive_withdraw_failed")
+                    .add_attribute("recipient", transfer.sender.to_string())
+                    .add_attribute("amount", amount.to_string())
+                    .add_attribute("channel_id", transfer.channel_id)
+                    .add_attribute("error", error)
+                    .add_attribute("balance", balance.to_string())));
+        }
+    };
+    let data = response
+        .data
+        .ok_or_else(|| StdError::generic_err("Missing IBC transfer response"))?;
+    let sequence = parse_transfer_sequence(&data)?;
+
+    IBC_TRANSFERS.save(deps.storage, (&transfer.channel_id, sequence), &transfer)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "ibc_transfer_sent")
+        .add_event(Event::new("thrive_ibc_transfer_sent")
+            .add_attribute("sender", transfer.sender.to_string())
+            .add_attribute("channel_id", transfer.channel_id)
+            .add_attribute("sequence", sequence.to_string())))
+}
+
+/// Encodes an ICS-20 `MsgTransfer` from the contract. Its memo asks ibc-hooks to report the
+/// packet's acknowledgement or timeout back to the contract through `sudo`.
+fn encode_ibc_transfer(
+    sender: &Addr,
+    channel_id: &str,
+    receiver: &str,
+    token: &Coin,
+    timeout: Timestamp,
+) -> Binary {
+    let mut coin = vec![];
+    proto_bytes(&mut coin, 1, token.denom.as_bytes());
+    proto_bytes(&mut coin, 2, token.amount.to_string().as_bytes());
+    let memo = format!(r#"{{"ibc_callback":"{}"}}"#, sender);
+
+    let mut msg = vec![];
+    proto_bytes(&mut msg, 1, b"transfer");
+    proto_bytes(&mut msg, 2, channel_id.as_bytes());
+    proto_bytes(&mut msg, 3, &coin);
+    proto_bytes(&mut msg, 4, sender.as_bytes());
+    proto_bytes(&mut msg, 5, receiver.as_bytes());
+    proto_varint(&mut msg, 7 << 3);
+    proto_varint(&mut msg, timeout.nanos());
+    proto_bytes(&mut msg, 8, memo.as_bytes());
+    Binary::from(msg)
+}
+
+/// Reads the `sequence` field (number 1) of a protobuf-encoded `MsgTransferResponse`.
+fn parse_transfer_sequence(data: &[u8]) -> StdResult<u64> {
+    fn read_varint(data: &[u8], pos: &mut usize) -> StdResult<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = *data
+                .get(*pos)
+                .ok_or_else(|| StdError::generic_err("Malformed IBC transfer response"))?;
+            *pos += 1;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(StdError::generic_err("Malformed IBC transfer response"))
+    }
+
+    let mut pos = 0;
+    while pos < data.len() {
+        let key = read_varint(data, &mut pos)?;
+        match (key >> 3, key & 0x7) {
+            (1, 0) => return read_varint(data, &mut pos),
+            (_, 0) => {
+                read_varint(data, &mut pos)?;
+            }
+            (_, 2) => {
+                let len = read_varint(data, &mut pos)? as usize;
+                pos = pos.saturating_add(len);
+            }
+            _ => return Err(StdError::generic_err("Malformed IBC transfer response")),
+        }
+    }
+    Err(StdError::generic_err("Missing IBC transfer sequence"))
+}
+
+/// Settles an IBC withdrawal once its packet is acknowledged or times out, crediting the
+/// amount back to the sender unless the transfer succeeded.
+fn complete_ibc_transfer(
+    deps: DepsMut,
+    env: Env,
+    channel: String,
+    sequence: u64,
+    success: bool,
+) -> StdResult<Response> {
+    let Some(transfer) = IBC_TRANSFERS.may_load(deps.storage, (&channel, sequence))? else {
+        return Ok(Response::new().add_attribute("action", "ibc_lifecycle_complete_unknown"));
+    };
+    IBC_TRANSFERS.remove(deps.storage, (&channel, sequence));
+
+    let event = Event::new(if success { "thrive_ibc_transfer_completed" } else { "thrive_ibc_transfer_failed" })
+        .add_attribute("sender", transfer.sender.to_string())
+        .add_attribute("channel_id", channel)
+        .add_attribute("sequence", sequence.to_string())
+        .add_attribute("amount", transfer.amount.to_string());
+    if success {
+        return Ok(Response::new()
+            .add_messages(fee_message(deps.storage, transfer.fee)?)
+            .add_attribute("action", "ibc_lifecycle_complete")
+            .add_event(event));
+    }
+
+    let amount = transfer.amount.checked_add(transfer.fee)?;
+    let balance = restore_withdrawal(deps.storage, &env.block, &transfer.sender, amount)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "ibc_lifecycle_complete")
+        .add_event(event.add_attribute("balance", balance.to_string())))
+}
+
+/// Settles a transfer whose acknowledgement or timeout never reached the contract, e.g. on a
+/// chain without ibc-hooks. Only allowed once the packet has timed out, so `success` reflects
+/// an outcome the owner can verify on the counterparty chain; a late callback is then ignored.
+pub fn execute_settle_ibc_transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    channel_id: String,
+    sequence: u64,
+    success: bool,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    let transfer = IBC_TRANSFERS
+        .may_load(deps.storage, (&channel_id, sequence))?
+        .ok_or_else(|| StdError::generic_err(format!("IBC transfer not found: {}/{}", channel_id, sequence)))?;
+    if env.block.time < transfer.timeout {
+        return Err(StdError::generic_err("IBC transfer has not timed out yet"));
+    }
+
+    complete_ibc_transfer(deps, env, channel_id, sequence, success)
+}
+
+pub fn execute_claim_unbonded(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> StdResult<Response> {
+    check_not_blocked(deps.storage, &info.sender)?;
+
+    let matured: Vec<UnbondingEntry> = unbonding()
+        .idx
+        .owner
+        .prefix(info.sender.clone())
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, entry)| entry))
+        .filter(|item| item.as_ref().map_or(true, |entry| entry.release_at <= env.block.time))
+        .collect::<StdResult<_>>()?;
+    if matured.is_empty() {
+        return Err(StdError::generic_err("No unbonded funds to claim"));
+    }
+
+    let mut amount = Uint128::zero();
+    for entry in &matured {
+        amount = amount.checked_add(entry.amount)?;
+        unbonding().remove(deps.storage, entry.id)?;
+    }
+
+    let denom = TOKEN_DENOM.load(deps.storage)?;
+    let (message, fee, payout) = payout_message(deps.storage, &info.sender, denom, amount)?;
+
+    Ok(Response::new()
+        .add_submessage(message)
+        .add_attribute("action", "claim_unbonded")
+        .add_attribute("amount", amount)
+        .add_event(Event::new("thrive_claim_unbonded")
+            .add_attribute("sender", info.sender.to_string())
+            .add_attribute("count", matured.len().to_string())
+            .add_attribute("amount", amount.to_string())
+            .add_attribute("fee", fee.to_string())
+            .add_attribute("payout", payout.to_string())))
+}
+
+pub fn update_ownership(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_owner: String,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    let new_owner_addr = deps.api.addr_validate(&new_owner)?;
+    let old_state = STATE.load(deps.storage)?;
+    STATE.save(deps.storage, &State { owner: new_owner_addr.clone() })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_ownership")
+        .add_attribute("new_owner", new_owner_addr.to_string())
+        .add_event(Event::new("thrive_update_ownership")
+            .add_attribute("old_owner", old_state.owner.to_string())
+            .add_attribute("new_owner", new_owner_addr.to_string())))
+}
+
+pub fn set_token_denom(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+    let old_denom = TOKEN_DENOM.load(deps.storage)?;
+    TOKEN_DENOM.save(deps.storage, &denom)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_token_denom")
+        .add_attribute("denom", denom.clone())
+        .add_event(Event::new("thrive_set_token_denom")
+            .add_attribute("old_denom", old_denom)
+            .add_attribute("new_denom", denom)))
+}
+
+pub fn set_max_batch_size(
+    deps: DepsMut,
+    info: MessageInfo,
+    max_batch_size: u32,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+    if max_batch_size == 0 {
+        return Err(StdError::generic_err("Max batch size must be greater than zero"));
+    }
+    let old_max_batch_size = query_max_batch_size(deps.as_ref())?;
+    MAX_BATCH_SIZE.save(deps.storage, &max_batch_size)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_max_batch_size")
+        .add_attribute("max_batch_size", max_batch_size.to_string())
+        .add_event(Event::new("thrive_set_max_batch_size")
+            .add_attribute("old_max_batch_size", old_max_batch_size.to_string())
+            .add_attribute("new_max_batch_size", max_batch_size.to_string())))
+}
+
+pub fn execute_fund_pool(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> StdResult<Response> {
+    let denom = TOKEN_DENOM.load(deps.storage)?;
+    let amount = sent_amount(&info, &denom);
+
+    if amount.is_zero() {
+        return Err(StdError::generic_err("Pool funding amount must be greater than zero"));
+    }
+
+    let pool = query_reward_pool(deps.as_ref())?.checked_add(amount)?;
+    REWARD_POOL.save(deps.storage, &pool)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "fund_pool")
+        .add_attribute("amount", amount)
+        .add_event(Event::new("thrive_fund_pool")
+            .add_attribute("sender", info.sender.to_string())
+            .add_attribute("amount", amount.to_string())
+            .add_attribute("pool", pool.to_string())))
+}
+
+pub fn execute_schedule_reward(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+    reason: String,
+    release_at: Timestamp,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    let recipient_addr = deps.api.addr_validate(&recipient)?;
+    if amount.is_zero() {
+        return Err(StdError::generic_err("Reward amount must be greater than zero"));
+    }
+    if release_at <= env.block.time {
+        return Err(StdError::generic_err("Release time must be in the future"));
+    }
+    check_not_blocked(deps.storage, &recipient_addr)?;
+    check_reason_length(&reason, None)?;
+    check_eligible(deps.storage, &recipient_addr)?;
+
+    check_approval_threshold(deps.storage, amount)?;
+    reserve_cap_usage(deps.storage, env.block.time, &recipient_addr, amount)?;
+
+    let pool = query_reward_pool(deps.as_ref())?;
+    if amount > pool {
+        return Err(StdError::generic_err("Insufficient reward pool"));
+    }
+    let pool = pool.checked_sub(amount)?;
+    REWARD_POOL.save(deps.storage, &pool)?;
+
+    let id = save_scheduled_reward(deps.storage, &recipient_addr, amount, &reason, release_at)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "schedule_reward")
+        .add_attribute("scheduled_reward_id", id.to_string())
+        .add_event(Event::new("thrive_schedule_reward")
+            .add_attribute("scheduled_reward_id", id.to_string())
+            .add_attribute("recipient", recipient_addr.to_string())
+            .add_attribute("amount", amount.to_string())
+            .add_attribute("reason", reason)
+            .add_attribute("release_at", release_at.seconds().to_string())
+            .add_attribute("pool", pool.to_string())))
+}
+
+fn save_scheduled_reward(
+    storage: &mut dyn Storage,
+    recipient: &Addr,
+    amount: Uint128,
+    reason: &str,
+    release_at: Timestamp,
+) -> StdResult<u64> {
+    let id = SCHEDULED_REWARD_COUNT.may_load(storage)?.unwrap_or_default() + 1;
+    SCHEDULED_REWARD_COUNT.save(storage, &id)?;
+    scheduled_rewards().save(storage, id, &ScheduledReward {
+        id,
+        recipient: recipient.clone(),
+        amount,
+        reason: reason.to_string(),
+        release_at,
+    })?;
+    Ok(id)
+}
+
+pub fn execute_cancel_scheduled_reward(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    let scheduled = scheduled_rewards()
+        .may_load(deps.storage, id)?
+        .ok_or_else(|| StdError::generic_err(format!("Scheduled reward not found: {}", id)))?;
+    if env.block.time >= scheduled.release_at {
+        return Err(StdError::generic_err("Scheduled reward already released"));
+    }
+
+    scheduled_rewards().remove(deps.storage, id)?;
+    release_cap_usage(deps.storage, &scheduled.recipient, scheduled.amount)?;
+    let pool = query_reward_pool(deps.as_ref())?.checked_add(scheduled.amount)?;
+    REWARD_POOL.save(deps.storage, &pool)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_scheduled_reward")
+        .add_attribute("scheduled_reward_id", id.to_string())
+        .add_event(Event::new("thrive_cancel_scheduled_reward")
+            .add_attribute("scheduled_reward_id", id.to_string())
+            .add_attribute("recipient", scheduled.recipient.to_string())
+            .add_attribute("amount", scheduled.amount.to_string())
+            .add_attribute("pool", pool.to_string())))
+}
+
+pub fn execute_release_scheduled_rewards(
+    deps: DepsMut,
+    env: Env,
+    address: String,
+) -> StdResult<Response> {
+    let addr = deps.api.addr_validate(&address)?;
+    let released = release_scheduled_rewards(deps, &env, &addr)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "release_scheduled_rewards")
+        .add_attribute("address", addr.to_string())
+        .add_attribute("count", released.len().to_string())
+        .add_events(released))
+}
+
+/// Credits every scheduled reward of `recipient` whose release time has passed. Rewards of
+/// blocked recipients stay pending until they are unblocked or the owner cancels them.
+fn release_scheduled_rewards(
+    mut deps: DepsMut,
+    env: &Env,
+    recipient: &Addr,
+) -> StdResult<Vec<Event>> {
+    if BLOCKED.has(deps.storage, recipient) {
+        return Ok(vec![]);
+    }
+    let matured: Vec<ScheduledReward> = scheduled_rewards()
+        .idx
+        .recipient
+        .prefix(recipient.clone())
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, reward)| reward))
+        .filter(|item| item.as_ref().map_or(true, |reward| reward.release_at <= env.block.time))
+        .collect::<StdResult<_>>()?;
+
+    let mut credits = PendingCredits::default();
+    let mut events = Vec::with_capacity(matured.len());
+    for scheduled in matured {
+        scheduled_rewards().remove(deps.storage, scheduled.id)?;
+        let (_, event) = reward_single(deps.branch(), env, &mut credits, PendingReward {
+            prefunded: true,
+            ..PendingReward::new(scheduled.recipient, scheduled.amount, scheduled.reason)
+        })?;
+        events.push(event.add_attribute("scheduled_reward_id", scheduled.id.to_string()));
+    }
+    save_credits(deps.storage, &env.block, credits)?;
+
+    Ok(events)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_create_stream(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    rate_per_second: Uint128,
+    start: Timestamp,
+    end: Timestamp,
+    reason: String,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    let recipient_addr = deps.api.addr_validate(&recipient)?;
+    if rate_per_second.is_zero() {
+        return Err(StdError::generic_err("Stream rate must be greater than zero"));
+    }
+    if start >= end {
+        return Err(StdError::generic_err("Stream start must be before its end"));
+    }
+    if start < env.block.time {
+        return Err(StdError::generic_err("Stream start must not be in the past"));
+    }
+    check_not_blocked(deps.storage, &recipient_addr)?;
+    check_reason_length(&reason, None)?;
+    check_eligible(deps.storage, &recipient_addr)?;
+
+    let deposit = rate_per_second.checked_mul(Uint128::from(end.seconds() - start.seconds()))?;
+    check_approval_threshold(deps.storage, deposit)?;
+    reserve_cap_usage(deps.storage, env.block.time, &recipient_addr, deposit)?;
+    let pool = query_reward_pool(deps.as_ref())?;
+    if deposit > pool {
+        return Err(StdError::generic_err("Insufficient reward pool"));
+    }
+    let pool = pool.checked_sub(deposit)?;
+    REWARD_POOL.save(deps.storage, &pool)?;
+
+    let id = STREAM_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    STREAM_COUNT.save(deps.storage, &id)?;
+    streams().save(deps.storage, id, &Stream {
+        id,
+        recipient: recipient_addr.clone(),
+        rate_per_second,
+        start,
+        end,
+        reason: reason.clone(),
+        deposit,
+        streamed: Uint128::zero(),
+        paused_at: None,
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "create_stream")
+        .add_attribute("stream_id", id.to_string())
+        .add_event(Event::new("thrive_create_stream")
+            .add_attribute("stream_id", id.to_string())
+            .add_attribute("recipient", recipient_addr.to_string())
+            .add_attribute("rate_per_second", rate_per_second.to_string())
+            .add_attribute("start", start.seconds().to_string())
+            .add_attribute("end", end.seconds().to_string())
+            .add_attribute("reason", reason)
+            .add_attribute("deposit", deposit.to_string())
+            .add_attribute("pool", pool.to_string())))
+}
+
+fn load_stream(storage: &dyn Storage, id: u64) -> StdResult<Stream> {
+    streams()
+        .may_load(storage, id)?
+        .ok_or_else(|| StdError::generic_err(format!("Stream not found: {}", id)))
+}
+
+pub fn execute_pause_stream(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    let mut stream = load_stream(deps.storage, id)?;
+    if stream.paused_at.is_some() {
+        return Err(StdError::generic_err("Stream already paused"));
+    }
+    if env.block.time >= stream.end {
+        return Err(StdError::generic_err("Stream already ended"));
+    }
+    stream.paused_at = Some(env.block.time);
+    streams().save(deps.storage, id, &stream)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "pause_stream")
+        .add_attribute("stream_id", id.to_string())
+        .add_event(Event::new("thrive_pause_stream")
+            .add_attribute("stream_id", id.to_string())
+            .add_attribute("paused_at", env.block.time.seconds().to_string())))
+}
+
+pub fn execute_resume_stream(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    let mut stream = load_stream(deps.storage, id)?;
+    let paused_at = stream
+        .paused_at
+        .ok_or_else(|| StdError::generic_err("Stream is not paused"))?;
+
+    // Shift the schedule by the time it was paused while running, so the recipient still
+    // receives the full deposit. Time paused before the start doesn't delay the stream.
+    let paused_from = paused_at.max(stream.start);
+    let paused_for = env.block.time.seconds().saturating_sub(paused_from.seconds());
+    stream.start = stream.start.plus_seconds(paused_for);
+    stream.end = stream.end.plus_seconds(paused_for);
+    stream.paused_at = None;
+    streams().save(deps.storage, id, &stream)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "resume_stream")
+        .add_attribute("stream_id", id.to_string())
+        .add_event(Event::new("thrive_resume_stream")
+            .add_attribute("stream_id", id.to_string())
+            .add_attribute("end", stream.end.seconds().to_string())))
+}
+
+pub fn execute_cancel_stream(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    let stream = load_stream(deps.storage, id)?;
+    let accrued = stream.accrued(env.block.time)?;
+    let mut credits = PendingCredits::default();
+    let mut events = vec![];
+    if accrued > stream.streamed {
+        let owed = accrued.checked_sub(stream.streamed)?;
+        if BLOCKED.has(deps.storage, &stream.recipient) {
+            // A blocked recipient keeps what it has accrued as a matured scheduled reward,
+            // released to it once it is unblocked.
+            let scheduled_id = save_scheduled_reward(deps.storage, &stream.recipient, owed, &stream.reason, env.block.time)?;
+            events.push(Event::new("thrive_hold_stream")
+                .add_attribute("stream_id", id.to_string())
+                .add_attribute("recipient", stream.recipient.to_string())
+                .add_attribute("amount", owed.to_string())
+                .add_attribute("scheduled_reward_id", scheduled_id.to_string()));
+        } else {
+            let (_, event) = reward_single(deps.branch(), &env, &mut credits, PendingReward {
+                prefunded: true,
+                ..PendingReward::new(stream.recipient.clone(), owed, stream.reason.clone())
+            })?;
+            events.push(event.add_attribute("stream_id", id.to_string()));
+        }
+    }
+    save_credits(deps.storage, &env.block, credits)?;
+    streams().remove(deps.storage, id)?;
+
+    let refund = stream.deposit.checked_sub(accrued)?;
+    release_cap_usage(deps.storage, &stream.recipient, refund)?;
+    let pool = query_reward_pool(deps.as_ref())?.checked_add(refund)?;
+    REWARD_POOL.save(deps.storage, &pool)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_stream")
+        .add_attribute("stream_id", id.to_string())
+        .add_events(events)
+        .add_event(Event::new("thrive_cancel_stream")
+            .add_attribute("stream_id", id.to_string())
+            .add_attribute("recipient", stream.recipient.to_string())
+            .add_attribute("refund", refund.to_string())
+            .add_attribute("pool", pool.to_string())))
+}
+
+/// Credits everything `recipient` has earned so far on their streams, removing finished ones.
+/// Streams of blocked recipients keep accruing without being credited.
+fn settle_streams(
+    mut deps: DepsMut,
+    env: &Env,
+    recipient: &Addr,
+) -> StdResult<Vec<Event>> {
+    if BLOCKED.has(deps.storage, recipient) {
+        return Ok(vec![]);
+    }
+    let recipient_streams: Vec<Stream> = streams()
+        .idx
+        .recipient
+        .prefix(recipient.clone())
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, stream)| stream))
+        .collect::<StdResult<_>>()?;
+
+    let mut credits = PendingCredits::default();
+    let mut events = vec![];
+    for mut stream in recipient_streams {
+        let accrued = stream.accrued(env.block.time)?;
+        if accrued > stream.streamed {
+            let (_, event) = reward_single(deps.branch(), env, &mut credits, PendingReward {
+                prefunded: true,
+                ..PendingReward::new(stream.recipient.clone(), accrued.checked_sub(stream.streamed)?, stream.reason.clone())
+            })?;
+            events.push(event.add_attribute("stream_id", stream.id.to_string()));
+            stream.streamed = accrued;
+        }
+        if stream.streamed == stream.deposit {
+            streams().remove(deps.storage, stream.id)?;
+        } else {
+            streams().save(deps.storage, stream.id, &stream)?;
+        }
+    }
+    save_credits(deps.storage, &env.block, credits)?;
+
+    Ok(events)
+}
+
+fn load_campaign(storage: &dyn Storage, id: u64) -> StdResult<Campaign> {
+    CAMPAIGNS
+        .may_load(storage, id)?
+        .ok_or_else(|| StdError::generic_err(format!("Campaign not found: {}", id)))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_create_campaign(
+    deps: DepsMut,
+    info: MessageInfo,
+    name: String,
+    budget: Uint128,
+    denom: String,
+    start: Timestamp,
+    end: Timestamp,
+    managers: Vec<String>,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    if denom != TOKEN_DENOM.load(deps.storage)? {
+        return Err(StdError::generic_err("Campaign denom must match the token denom"));
+    }
+    if start >= end {
+        return Err(StdError::generic_err("Campaign start must be before its end"));
+    }
+    let managers = managers
+        .iter()
+        .map(|manager| deps.api.addr_validate(manager))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let id = CAMPAIGN_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    CAMPAIGN_COUNT.save(deps.storage, &id)?;
+    CAMPAIGNS.save(deps.storage, id, &Campaign {
+        id,
+        name: name.clone(),
+        budget,
+        denom,
+        start,
+        end,
+        managers: managers.clone(),
+        spent: Uint128::zero(),
+    })?;
+
+    let managers = managers.iter().map(Addr::as_str).collect::<Vec<_>>().join(",");
+    Ok(Response::new()
+        .add_attribute("action", "create_campaign")
+        .add_attribute("campaign_id", id.to_string())
+        .add_event(Event::new("thrive_create_campaign")
+            .add_attribute("campaign_id", id.to_string())
+            .add_attribute("name", name)
+            .add_attribute("budget", budget.to_string())
+            .add_attribute("start", start.seconds().to_string())
+            .add_attribute("end", end.seconds().to_string())
+            .add_attribute("managers", managers)))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_set_delegate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+    quota: Uint128,
+    period_seconds: u64,
+    expires_at: Option<Timestamp>,
+    reason_prefix: Option<String>,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    let addr = deps.api.addr_validate(&address)?;
+    if period_seconds == 0 {
+        return Err(StdError::generic_err("Delegate period must be greater than zero"));
+    }
+    // Updating an existing delegate keeps its usage in the current period, so re-setting the
+    // quota can't be used to reset what it has already spent.
+    let existing = DELEGATES.may_load(deps.storage, &addr)?;
+    let (period_start, used) = existing
+        .as_ref()
+        .map(|delegate| (delegate.period_start, delegate.used))
+        .unwrap_or((env.block.time, Uint128::zero()));
+    DELEGATES.save(deps.storage, &addr, &Delegate {
+        address: addr.clone(),
+        quota,
+        period_seconds,
+        expires_at,
+        reason_prefix: reason_prefix.clone(),
+        period_start,
+        used,
+    })?;
+
+    let mut event = Event::new("thrive_set_delegate")
+        .add_attribute("delegate", addr.to_string())
+        .add_attribute("quota", quota.to_string())
+        .add_attribute("period_seconds", period_seconds.to_string());
+    if let Some(expires_at) = expires_at {
+        event = event.add_attribute("expires_at", expires_at.seconds().to_string());
+    }
+    if let Some(reason_prefix) = reason_prefix {
+        event = event.add_attribute("reason_prefix", reason_prefix);
+    }
+    if let Some(old) = existing {
+        event = event
+            .add_attribute("old_quota", old.quota.to_string())
+            .add_attribute("old_period_seconds", old.period_seconds.to_string())
+            .add_attribute("old_expires_at", old.expires_at.map(|t| t.seconds().to_string()).unwrap_or_default())
+            .add_attribute("old_reason_prefix", old.reason_prefix.unwrap_or_default());
+    }
+    Ok(Response::new()
+        .add_attribute("action", "set_delegate")
+        .add_attribute("delegate", addr.to_string())
+        .add_event(event))
+}
+
+pub fn execute_remove_delegate(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    let addr = deps.api.addr_validate(&address)?;
+    if !DELEGATES.has(deps.storage, &addr) {
+        return Err(StdError::generic_err(format!("Delegate not found: {}", addr)));
+    }
+    DELEGATES.remove(deps.storage, &addr);
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_delegate")
+        .add_attribute("delegate", addr.to_string())
+        .add_event(Event::new("thrive_remove_delegate").add_attribute("delegate", addr.to_string())))
+}
+
+pub fn execute_set_approval_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    approvers: Vec<String>,
+    threshold: u32,
+    amount_threshold: Uint128,
+    proposal_ttl_seconds: u64,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    let approvers = approvers
+        .iter()
+        .map(|approver| deps.api.addr_validate(approver))
+        .collect::<StdResult<Vec<_>>>()?;
+    if threshold == 0 || threshold as usize > approvers.len() {
+        return Err(StdError::generic_err("Approval threshold must be between one and the number of approvers"));
+    }
+    if proposal_ttl_seconds == 0 {
+        return Err(StdError::generic_err("Proposal ttl must be greater than zero"));
+    }
+    let old_config = APPROVAL_CONFIG.may_load(deps.storage)?;
+    APPROVAL_CONFIG.save(deps.storage, &ApprovalConfig {
+        approvers: approvers.clone(),
+        threshold,
+        amount_threshold,
+        proposal_ttl_seconds,
+    })?;
+
+    let join = |approvers: &[Addr]| approvers.iter().map(Addr::as_str).collect::<Vec<_>>().join(",");
+    Ok(Response::new()
+        .add_attribute("action", "set_approval_config")
+        .add_event(Event::new("thrive_set_approval_config")
+            .add_attribute("old_approvers", old_config.as_ref().map(|c| join(&c.approvers)).unwrap_or_default())
+            .add_attribute("new_approvers", join(&approvers))
+            .add_attribute("old_threshold", old_config.as_ref().map(|c| c.threshold).unwrap_or_default().to_string())
+            .add_attribute("new_threshold", threshold.to_string())
+            .add_attribute(
+                "old_amount_threshold",
+                old_config.as_ref().map(|c| c.amount_threshold).unwrap_or_default().to_string(),
+            )
+            .add_attribute("new_amount_threshold", amount_threshold.to_string())
+            .add_attribute(
+                "old_proposal_ttl_seconds",
+                old_config.map(|c| c.proposal_ttl_seconds).unwrap_or_default().to_string(),
+            )
+            .add_attribute("new_proposal_ttl_seconds", proposal_ttl_seconds.to_string())))
+}
+
+fn load_proposal(storage: &dyn Storage, id: u64) -> StdResult<RewardProposal> {
+    PROPOSALS
+        .may_load(storage, id)?
+        .ok_or_else(|| StdError::generic_err(format!("Proposal not found: {}", id)))
+}
+
+pub fn execute_propose_reward(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+    reason: String,
+) -> StdResult<Response> {
+    let config = APPROVAL_CONFIG
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("Approval config not set"))?;
+    let is_approver = config.approvers.contains(&info.sender);
+    if !is_approver {
+        validate_owner(deps.as_ref(), &info)?;
+    }
+
+    let recipient_addr = deps.api.addr_validate(&recipient)?;
+    if amount.is_zero() {
+        return Err(StdError::generic_err("Reward amount must be greater than zero"));
+    }
+    check_not_blocked(deps.storage, &recipient_addr)?;
+    check_reason_length(&reason, None)?;
+
+    let id = PROPOSAL_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    PROPOSAL_COUNT.save(deps.storage, &id)?;
+    let expires_at = env.block.time.plus_seconds(config.proposal_ttl_seconds);
+    PROPOSALS.save(deps.storage, id, &RewardProposal {
+        id,
+        recipient: recipient_addr.clone(),
+        amount,
+        reason: reason.clone(),
+        proposer: info.sender.clone(),
+        approvals: vec![],
+        expires_at,
+        status: ProposalStatus::Pending,
+    })?;
+
+    let mut response = Response::new()
+        .add_attribute("action", "propose_reward")
+        .add_attribute("proposal_id", id.to_string())
+        .add_event(Event::new("thrive_propose_reward")
+            .add_attribute("proposal_id", id.to_string())
+            .add_attribute("proposer", info.sender.to_string())
+            .add_attribute("recipient", recipient_addr.to_string())
+            .add_attribute("amount", amount.to_string())
+            .add_attribute("reason", reason)
+            .add_attribute("expires_at", expires_at.seconds().to_string()));
+
+    // An approver proposing counts as the first approval.
+    if is_approver {
+        let approval = approve_proposal(deps, &env, &info, &config, id)?;
+        response = response.add_events(approval);
+    }
+    Ok(response)
+}
+
+pub fn execute_approve_reward(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> StdResult<Response> {
+    let config = APPROVAL_CONFIG
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("Approval config not set"))?;
+    if !config.approvers.contains(&info.sender) {
+        return Err(StdError::generic_err("Unauthorized: Only approvers can approve rewards"));
+    }
+
+    let events = approve_proposal(deps, &env, &info, &config, id)?;
+    Ok(Response::new()
+        .add_attribute("action", "approve_reward")
+        .add_attribute("proposal_id", id.to_string())
+        .add_events(events))
+}
+
+/// Records the sender's approval and pays the reward once the approval threshold is reached,
+/// or rejects it if the recipient has been blocked in the meantime.
+fn approve_proposal(
+    mut deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    config: &ApprovalConfig,
+    id: u64,
+) -> StdResult<Vec<Event>> {
+    let mut proposal = load_proposal(deps.storage, id)?;
+    match proposal.status {
+        ProposalStatus::Pending => {}
+        ProposalStatus::Executed => return Err(StdError::generic_err("Proposal already executed")),
+        ProposalStatus::Rejected => return Err(StdError::generic_err("Proposal was rejected")),
+    }
+    if env.block.time >= proposal.expires_at {
+        return Err(StdError::generic_err("Proposal expired"));
+    }
+    if proposal.approvals.contains(&info.sender) {
+        return Err(StdError::generic_err("Proposal already approved by sender"));
+    }
+    proposal.approvals.push(info.sender.clone());
+
+    let mut events = vec![Event::new("thrive_approve_reward")
+        .add_attribute("proposal_id", id.to_string())
+        .add_attribute("approver", info.sender.to_string())
+        .add_attribute("approvals", proposal.approvals.len().to_string())];
+
+    if proposal.approvals.len() >= config.threshold as usize && BLOCKED.has(deps.storage, &proposal.recipient) {
+        proposal.status = ProposalStatus::Rejected;
+        events.push(Event::new("thrive_reject_reward")
+            .add_attribute("proposal_id", id.to_string())
+            .add_attribute("recipient", proposal.recipient.to_string())
+            .add_attribute("reason", "recipient_blocked"));
+    } else if proposal.approvals.len() >= config.threshold as usize {
+        let mut credits = PendingCredits::default();
+        let (_, event) = reward_single(
+            deps.branch(), env, &mut credits,
+            PendingReward::new(proposal.recipient.clone(), proposal.amount, proposal.reason.clone())
+        )?;
+        save_credits(deps.storage, &env.block, credits)?;
+        proposal.status = ProposalStatus::Executed;
+        events.push(event.add_attribute("proposal_id", id.to_string()));
+    }
+    PROPOSALS.save(deps.storage, id, &proposal)?;
+
+    Ok(events)
+}
+
+pub fn execute_set_snapshot_interval(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    interval: u64,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    let (_, old_interval) = snapshot_interval_at(deps.storage, env.block.height)?;
+    SNAPSHOT_INTERVALS.save(deps.storage, env.block.height, &interval)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_snapshot_interval")
+        .add_attribute("interval", interval.to_string())
+        .add_event(Event::new("thrive_set_snapshot_interval")
+            .add_attribute("old_interval", old_interval.to_string())
+            .add_attribute("new_interval", interval.to_string())
+            .add_attribute("height", env.block.height.to_string())))
+}
+
+pub fn execute_update_fee_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    fee_bps: u16,
+    min_fee: Option<Uint128>,
+    collector: String,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    if fee_bps > MAX_FEE_BPS {
+        return Err(StdError::generic_err(format!("Fee cannot exceed {} basis points", MAX_FEE_BPS)));
+    }
+    let config = FeeConfig {
+        fee_bps,
+        min_fee: min_fee.unwrap_or_default(),
+        collector: deps.api.addr_validate(&collector)?,
+    };
+    let old_config = FEE_CONFIG.may_load(deps.storage)?;
+    FEE_CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_fee_config")
+        .add_event(Event::new("thrive_update_fee_config")
+            .add_attribute("old_fee_bps", old_config.as_ref().map(|c| c.fee_bps).unwrap_or_default().to_string())
+            .add_attribute("new_fee_bps", config.fee_bps.to_string())
+            .add_attribute("old_min_fee", old_config.as_ref().map(|c| c.min_fee).unwrap_or_default().to_string())
+            .add_attribute("new_min_fee", config.min_fee.to_string())
+            .add_attribute("old_collector", old_config.map(|c| c.collector.to_string()).unwrap_or_default())
+            .add_attribute("new_collector", config.collector.to_string())))
+}
+
+pub fn execute_set_unbonding_delay(
+    deps: DepsMut,
+    info: MessageInfo,
+    delay_seconds: u64,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    let old_delay = UNBONDING_DELAY.may_load(deps.storage)?.unwrap_or_default();
+    UNBONDING_DELAY.save(deps.storage, &delay_seconds)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_unbonding_delay")
+        .add_attribute("delay_seconds", delay_seconds.to_string())
+        .add_event(Event::new("thrive_set_unbonding_delay")
+            .add_attribute("old_delay_seconds", old_delay.to_string())
+            .add_attribute("new_delay_seconds", delay_seconds.to_string())))
+}
+
+pub fn execute_update_dust_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    min_withdraw: Uint128,
+    inactivity_seconds: u64,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    let old_config = DUST_CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    DUST_CONFIG.save(deps.storage, &DustConfig { min_withdraw, inactivity_seconds })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_dust_config")
+        .add_event(Event::new("thrive_update_dust_config")
+            .add_attribute("old_min_withdraw", old_config.min_withdraw.to_string())
+            .add_attribute("new_min_withdraw", min_withdraw.to_string())
+            .add_attribute("old_inactivity_seconds", old_config.inactivity_seconds.to_string())
+            .add_attribute("new_inactivity_seconds", inactivity_seconds.to_string())))
+}
+
+/// Moves balances below `threshold` of accounts inactive for the configured period back into
+/// the reward pool. Accounts are scanned in address order; `last_address` in the response is
+/// the `start_after` for the next page.
+///
+/// Only balances too small to withdraw count as dust, so `threshold` may not exceed
+/// `min_withdraw`. With the default dust config (`min_withdraw` of zero) nothing can be swept
+/// until the owner sets a minimum withdrawal through `UpdateDustConfig`.
+pub fn execute_sweep_dust(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    threshold: Uint128,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    let config = DUST_CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    if threshold > config.min_withdraw {
+        return Err(StdError::generic_err(format!(
+            "Dust threshold cannot exceed the minimum withdrawal of {}", config.min_withdraw
+        )));
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|addr| deps.api.addr_validate(&addr)).transpose()?;
+    let accounts: Vec<(Addr, Uint128)> = BALANCES
+        .range(deps.storage, start.as_ref().map(Bound::exclusive), None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+
+    let mut events = vec![];
+    let mut swept = Uint128::zero();
+    for (addr, balance) in &accounts {
+        if balance.is_zero() || *balance >= threshold {
+            continue;
+        }
+        if BLOCKED.may_load(deps.storage, addr)?.is_some_and(|blocked| blocked.freeze) {
+            continue;
+        }
+        let last_active = LAST_ACTIVE.may_load(deps.storage, addr)?.unwrap_or_default();
+        if last_active.plus_seconds(config.inactivity_seconds) > env.block.time {
+            continue;
+        }
+        save_balance(deps.storage, &env.block, addr, Uint128::zero())?;
+        swept = swept.checked_add(*balance)?;
+        events.push(Event::new("thrive_sweep_dust")
+            .add_attribute("address", addr.to_string())
+            .add_attribute("amount", balance.to_string()));
+    }
+
+    let pool = REWARD_POOL.may_load(deps.storage)?.unwrap_or_default();
+    REWARD_POOL.save(deps.storage, &pool.checked_add(swept)?)?;
+
+    let last_address = accounts.last().map(|(addr, _)| addr.to_string()).unwrap_or_default();
+    Ok(Response::new()
+        .add_attribute("action", "sweep_dust")
+        .add_attribute("count", events.len().to_string())
+        .add_attribute("amount", swept)
+        .add_attribute("last_address", last_address)
+        .add_events(events))
+}
+
+pub fn execute_set_auto_payout(
+    deps: DepsMut,
+    info: MessageInfo,
+    auto: bool,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    let old_auto = AUTO_PAYOUT.may_load(deps.storage)?.unwrap_or_default();
+    AUTO_PAYOUT.save(deps.storage, &auto)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_auto_payout")
+        .add_attribute("auto", auto.to_string())
+        .add_event(Event::new("thrive_set_auto_payout")
+            .add_attribute("old_auto", old_auto.to_string())
+            .add_attribute("new_auto", auto.to_string())))
+}
+
+pub fn execute_set_payout_preference(
+    deps: DepsMut,
+    info: MessageInfo,
+    auto: bool,
+) -> StdResult<Response> {
+    PAYOUT_PREFERENCES.save(deps.storage, &info.sender, &auto)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_payout_preference")
+        .add_attribute("auto", auto.to_string())
+        .add_event(Event::new("thrive_set_payout_preference")
+            .add_attribute("address", info.sender.to_string())
+            .add_attribute("auto", auto.to_string())))
+}
+
+pub fn execute_set_voucher_signer(
+    deps: DepsMut,
+    info: MessageInfo,
+    pubkey: Option<Binary>,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    let old_pubkey = VOUCHER_SIGNER.may_load(deps.storage)?;
+    match &pubkey {
+        Some(pubkey) => {
+            if pubkey.len() != 33 && pubkey.len() != 65 {
+                return Err(StdError::generic_err("Voucher signer must be a secp256k1 public key"));
+            }
+            VOUCHER_SIGNER.save(deps.storage, pubkey)?;
+        }
+        None => VOUCHER_SIGNER.remove(deps.storage),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_voucher_signer")
+        .add_event(Event::new("thrive_set_voucher_signer")
+            .add_attribute("old_pubkey", old_pubkey.map(|p| p.to_base64()).unwrap_or_default())
+            .add_attribute("new_pubkey", pubkey.map(|p| p.to_base64()).unwrap_or_default())))
+}
+
+/// Credits a reward voucher signed by the configured signer. Anyone may submit a voucher;
+/// the reward always goes to the recipient named in it.
+pub fn execute_claim_voucher(
+    mut deps: DepsMut,
+    env: Env,
+    voucher: RewardVoucher,
+    signature: Binary,
+) -> StdResult<Response> {
+    let pubkey = VOUCHER_SIGNER
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("Voucher claims are not enabled"))?;
+    if voucher.contract_address != env.contract.address.as_str() || voucher.chain_id != env.block.chain_id {
+        return Err(StdError::generic_err("Voucher was issued for a different contract"));
+    }
+    if voucher.expires_at <= env.block.time {
+        return Err(StdError::generic_err("Voucher expired"));
+    }
+    if USED_VOUCHER_NONCES.has(deps.storage, &voucher.nonce) {
+        return Err(StdError::generic_err(format!("Voucher nonce already used: {}", voucher.nonce)));
+    }
+    check_reason_length(&voucher.reason, None)?;
+
+    let hash = Sha256::digest(to_json_vec(&voucher)?);
+    let valid = deps
+        .api
+        .secp256k1_verify(&hash, &signature, &pubkey)
+        .map_err(|err| StdError::generic_err(format!("Invalid voucher signature: {}", err)))?;
+    if !valid {
+        return Err(StdError::generic_err("Invalid voucher signature"));
+    }
+    check_approval_threshold(deps.storage, voucher.amount)?;
+
+    let recipient = deps.api.addr_validate(&voucher.recipient)?;
+    let auto_payout = resolve_auto_payout(deps.storage, &recipient, None)?;
+    let mut credits = PendingCredits::default();
+    let (reward_id, event) = reward_single(deps.branch(), &env, &mut credits, PendingReward {
+        auto_payout,
+        ..PendingReward::new(recipient, voucher.amount, voucher.reason)
+    })?;
+    let grants = fee_grant_messages(deps.storage, &env, credits.first_rewarded.iter())?;
+    let messages = save_credits(deps.storage, &env.block, credits)?;
+    USED_VOUCHER_NONCES.save(deps.storage, &voucher.nonce, &reward_id)?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_submessages(grants)
+        .add_attribute("action", "claim_voucher")
+        .add_attribute("nonce", &voucher.nonce)
+        .add_event(event.add_attribute("voucher_nonce", voucher.nonce)))
+}
+
+pub fn execute_block_address(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    freeze: bool,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    let addr = deps.api.addr_validate(&address)?;
+    let old = BLOCKED.may_load(deps.storage, &addr)?;
+    BLOCKED.save(deps.storage, &addr, &BlockedAddress { address: addr.clone(), freeze })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "block_address")
+        .add_attribute("address", addr.to_string())
+        .add_event(Event::new("thrive_block_address")
+            .add_attribute("address", addr.to_string())
+            .add_attribute("old_blocked", old.is_some().to_string())
+            .add_attribute("old_freeze", old.map(|b| b.freeze).unwrap_or_default().to_string())
+            .add_attribute("freeze", freeze.to_string())))
+}
+
+pub fn execute_unblock_address(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    let addr = deps.api.addr_validate(&address)?;
+    if !BLOCKED.has(deps.storage, &addr) {
+        return Err(StdError::generic_err(format!("Address is not blocked: {}", addr)));
+    }
+    BLOCKED.remove(deps.storage, &addr);
+
+    Ok(Response::new()
+        .add_attribute("action", "unblock_address")
+        .add_attribute("address", addr.to_string())
+        .add_event(Event::new("thrive_unblock_address").add_attribute("address", addr.to_string())))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_set_eligibility_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    allowlist_only: bool,
+    merkle_root: Option<Binary>,
+    lifetime_cap: Option<Uint128>,
+    period_cap: Option<Uint128>,
+    period_seconds: u64,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    if merkle_root.as_ref().is_some_and(|root| root.len() != 32) {
+        return Err(StdError::generic_err("Merkle root must be a 32 byte SHA-256 hash"));
+    }
+    if period_cap.is_some() && period_seconds == 0 {
+        return Err(StdError::generic_err("Cap period must be greater than zero"));
+    }
+    let config = EligibilityConfig { allowlist_only, merkle_root, lifetime_cap, period_cap, period_seconds };
+    let old_config = ELIGIBILITY_CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    ELIGIBILITY_CONFIG.save(deps.storage, &config)?;
+
+    let cap = |cap: Option<Uint128>| cap.map(|cap| cap.to_string()).unwrap_or_default();
+    let root = |root: Option<Binary>| root.map(|root| root.to_base64()).unwrap_or_default();
+    Ok(Response::new()
+        .add_attribute("action", "set_eligibility_config")
+        .add_event(Event::new("thrive_set_eligibility_config")
+            .add_attribute("old_allowlist_only", old_config.allowlist_only.to_string())
+            .add_attribute("new_allowlist_only", allowlist_only.to_string())
+            .add_attribute("old_merkle_root", root(old_config.merkle_root))
+            .add_attribute("new_merkle_root", root(config.merkle_root))
+            .add_attribute("old_lifetime_cap", cap(old_config.lifetime_cap))
+            .add_attribute("new_lifetime_cap", cap(lifetime_cap))
+            .add_attribute("old_period_cap", cap(old_config.period_cap))
+            .add_attribute("new_period_cap", cap(period_cap))
+            .add_attribute("old_period_seconds", old_config.period_seconds.to_string())
+            .add_attribute("new_period_seconds", period_seconds.to_string())))
+}
+
+pub fn execute_update_eligible(
+    deps: DepsMut,
+    info: MessageInfo,
+    addresses: Vec<String>,
+    eligible: bool,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    let mut events = Vec::with_capacity(addresses.len());
+    for address in &addresses {
+        let addr = deps.api.addr_validate(address)?;
+        if eligible {
+            ELIGIBLE.save(deps.storage, &addr, &Empty {})?;
+        } else {
+            ELIGIBLE.remove(deps.storage, &addr);
+        }
+        events.push(Event::new("thrive_update_eligible")
+            .add_attribute("address", addr.to_string())
+            .add_attribute("eligible", eligible.to_string()));
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", if eligible { "add_eligible" } else { "remove_eligible" })
+        .add_attribute("count", addresses.len().to_string())
+        .add_events(events))
+}
+
+/// Adds `address` to the allowlist given a Merkle proof against the configured root. Leaves are
+/// the SHA-256 of the address and each pair of nodes is hashed in ascending byte order.
+pub fn execute_prove_eligibility(
+    deps: DepsMut,
+    address: String,
+    proof: Vec<Binary>,
+) -> StdResult<Response> {
+    let addr = deps.api.addr_validate(&address)?;
+    let root = ELIGIBILITY_CONFIG
+        .may_load(deps.storage)?
+        .and_then(|config| config.merkle_root)
+        .ok_or_else(|| StdError::generic_err("No eligibility Merkle root is configured"))?;
+
+    let mut hash: [u8; 32] = Sha256::digest(addr.as_bytes()).into();
+    for node in &proof {
+        let (first, second) = if hash.as_slice() < node.as_slice() {
+            (hash.as_slice(), node.as_slice())
+        } else {
+            (node.as_slice(), hash.as_slice())
+        };
+        hash = Sha256::new().chain_update(first).chain_update(second).finalize().into();
+    }
+    if hash.as_slice() != root.as_slice() {
+        return Err(StdError::generic_err("Invalid eligibility proof"));
+    }
+    ELIGIBLE.save(deps.storage, &addr, &Empty {})?;
+
+    Ok(Response::new()
+        .add_attribute("action", "prove_eligibility")
+        .add_attribute("address", addr.to_string())
+        .add_event(Event::new("thrive_update_eligible")
+            .add_attribute("address", addr.to_string())
+            .add_attribute("eligible", "true")))
+}
+
+pub fn execute_set_ibc_channel(
+    deps: DepsMut,
+    info: MessageInfo,
+    channel_id: String,
+    allowed: bool,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    let old_allowed = IBC_CHANNELS.has(deps.storage, &channel_id);
+    if allowed {
+        IBC_CHANNELS.save(deps.storage, &channel_id, &Empty {})?;
+    } else {
+        IBC_CHANNELS.remove(deps.storage, &channel_id);
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_ibc_channel")
+        .add_attribute("channel_id", &channel_id)
+        .add_event(Event::new("thrive_set_ibc_channel")
+            .add_attribute("channel_id", channel_id)
+            .add_attribute("old_allowed", old_allowed.to_string())
+            .add_attribute("new_allowed", allowed.to_string())))
+}
+
+/// Sets the fee grant allowance. Funds sent in `denom` are added to the budget, which carries
+/// over from the previous config; the denom can only change once the budget is spent.
+pub fn execute_set_fee_grant_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    spend_limit: Uint128,
+    expiration_seconds: Option<u64>,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    if spend_limit.is_zero() {
+        return Err(StdError::generic_err("Fee grant spend limit must be greater than zero"));
+    }
+    let old_config = FEE_GRANT_CONFIG.may_load(deps.storage)?;
+    let old_budget = old_config.as_ref().map(|c| c.budget).unwrap_or_default();
+    if old_config.as_ref().is_some_and(|c| c.denom != denom) && !old_budget.is_zero() {
+        return Err(StdError::generic_err("Fee grant denom cannot change while budget remains"));
+    }
+    let budget = old_budget.checked_add(sent_amount(&info, &denom))?;
+    FEE_GRANT_CONFIG.save(deps.storage, &FeeGrantConfig {
+        denom: denom.clone(),
+        spend_limit,
+        expiration_seconds,
+        budget,
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_fee_grant_config")
+        .add_event(Event::new("thrive_set_fee_grant_config")
+            .add_attribute("old_denom", old_config.as_ref().map(|c| c.denom.clone()).unwrap_or_default())
+            .add_attribute("new_denom", denom)
+            .add_attribute("old_spend_limit", old_config.as_ref().map(|c| c.spend_limit).unwrap_or_default().to_string())
+            .add_attribute("new_spend_limit", spend_limit.to_string())
+            .add_attribute(
+                "old_expiration_seconds",
+                old_config.as_ref().and_then(|c| c.expiration_seconds).map(|s| s.to_string()).unwrap_or_default(),
+            )
+            .add_attribute("new_expiration_seconds", expiration_seconds.map(|s| s.to_string()).unwrap_or_default())
+            .add_attribute("old_budget", old_budget.to_string())
+            .add_attribute("new_budget", budget.to_string())))
+}
+
+pub fn execute_fund_fee_grants(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> StdResult<Response> {
+    let mut config = FEE_GRANT_CONFIG
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("Fee grant config not set"))?;
+    let amount = sent_amount(&info, &config.denom);
+    if amount.is_zero() {
+        return Err(StdError::generic_err("Fee grant funding amount must be greater than zero"));
+    }
+    config.budget = config.budget.checked_add(amount)?;
+    FEE_GRANT_CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "fund_fee_grants")
+        .add_attribute("amount", amount)
+        .add_event(Event::new("thrive_fund_fee_grants")
+            .add_attribute("sender", info.sender.to_string())
+            .add_attribute("amount", amount.to_string())
+            .add_attribute("budget", config.budget.to_string())))
+}
+
+/// Issues a fee allowance to each of `recipients`, the addresses rewarded for the first time,
+/// as long as the fee grant budget covers it. Grants are submessages so that one the chain rejects, for
+/// instance because the address already has an allowance, doesn't fail the reward.
+fn fee_grant_messages<'a>(
+    storage: &mut dyn Storage,
+    env: &Env,
+    recipients: impl Iterator<Item = &'a Addr>,
+) -> StdResult<Vec<SubMsg>> {
+    let Some(mut config) = FEE_GRANT_CONFIG.may_load(storage)? else {
+        return Ok(vec![]);
+    };
+
+    let mut messages = vec![];
+    for recipient in recipients {
+        if config.budget < config.spend_limit {
+            break;
+        }
+        if FEE_GRANTS.has(storage, recipient) {
+            continue;
+        }
+        config.budget = config.budget.checked_sub(config.spend_limit)?;
+
+        let id = FEE_GRANT_COUNT.may_load(storage)?.unwrap_or_default() + 1;
+        FEE_GRANT_COUNT.save(storage, &id)?;
+        let expires_at = config.expiration_seconds.map(|seconds| env.block.time.plus_seconds(seconds));
+        FEE_GRANTS.save(storage, recipient, &FeeGrant {
+            id,
+            amount: config.spend_limit,
+            granted_at: env.block.time,
+            expires_at,
+        })?;
+        PENDING_FEE_GRANTS.save(storage, id, recipient)?;
+
+        let msg = CosmosMsg::Stargate {
+            type_url: "/cosmos.feegrant.v1beta1.MsgGrantAllowance".to_string(),
+            value: encode_grant_allowance(&env.contract.address, recipient, &config, expires_at),
+        };
+        messages.push(SubMsg::reply_always(msg, FEE_GRANT_REPLY_ID_OFFSET + id));
+    }
+    FEE_GRANT_CONFIG.save(storage, &config)?;
+    Ok(messages)
+}
+
+/// Protobuf encoding of a `MsgGrantAllowance` carrying a `BasicAllowance`.
+fn encode_grant_allowance(
+    granter: &Addr,
+    grantee: &Addr,
+    config: &FeeGrantConfig,
+    expires_at: Option<Timestamp>,
+) -> Binary {
+    let mut coin = vec![];
+    proto_bytes(&mut coin, 1, config.denom.as_bytes());
+    proto_bytes(&mut coin, 2, config.spend_limit.to_string().as_bytes());
+
+    let mut allowance = vec![];
+    proto_bytes(&mut allowance, 1, &coin);
+    if let Some(expires_at) = expires_at {
+        let mut timestamp = vec![];
+        proto_varint(&mut timestamp, 1 << 3);
+        proto_varint(&mut timestamp, expires_at.seconds());
+        proto_bytes(&mut allowance, 2, &timestamp);
+    }
+
+    let mut any = vec![];
+    proto_bytes(&mut any, 1, b"/cosmos.feegrant.v1beta1.BasicAllowance");
+    proto_bytes(&mut any, 2, &allowance);
+
+    let mut msg = vec![];
+    proto_bytes(&mut msg, 1, granter.as_bytes());
+    proto_bytes(&mut msg, 2, grantee.as_bytes());
+    proto_bytes(&mut msg, 3, &any);
+    Binary::from(msg)
+}
+
+fn proto_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+/// Appends a length-delimited field.
+fn proto_bytes(buf: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+    proto_varint(buf, field << 3 | 2);
+    proto_varint(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+/// Returns a rejected grant's amount to the budget so the address can be granted again later.
+fn reply_fee_grant(deps: DepsMut, id: u64, result: SubMsgResult) -> StdResult<Response> {
+    let grantee = PENDING_FEE_GRANTS.load(deps.storage, id)?;
+    PENDING_FEE_GRANTS.remove(deps.storage, id);
+    let SubMsgResult::Err(error) = result else {
+        return Ok(Response::new());
+    };
+
+    let grant = FEE_GRANTS.load(deps.storage, &grantee)?;
+    FEE_GRANTS.remove(deps.storage, &grantee);
+    let mut config = FEE_GRANT_CONFIG.load(deps.storage)?;
+    config.budget = config.budget.checked_add(grant.amount)?;
+    FEE_GRANT_CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "fee_grant_failed")
+        .add_event(Event::new("thrive_fee_grant_failed")
+            .add_attribute("grantee", grantee.to_string())
+            .add_attribute("error", error)
+            .add_attribute("budget", config.budget.to_string())))
+}
+
+pub fn execute_register_operator_tag(
+    deps: DepsMut,
+    info: MessageInfo,
+    tag: String,
+    grantee: String,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    if tag.is_empty() {
+        return Err(StdError::generic_err("Operator tag must not be empty"));
+    }
+    let grantee = deps.api.addr_validate(&grantee)?;
+    let old_grantee = OPERATOR_TAGS.may_load(deps.storage, &tag)?;
+    OPERATOR_TAGS.save(deps.storage, &tag, &grantee)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "register_operator_tag")
+        .add_attribute("tag", &tag)
+        .add_event(Event::new("thrive_register_operator_tag")
+            .add_attribute("tag", tag)
+            .add_attribute("old_grantee", old_grantee.map(|g| g.to_string()).unwrap_or_default())
+            .add_attribute("grantee", grantee.to_string())))
+}
+
+pub fn execute_remove_operator_tag(
+    deps: DepsMut,
+    info: MessageInfo,
+    tag: String,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    if !OPERATOR_TAGS.has(deps.storage, &tag) {
+        return Err(StdError::generic_err(format!("Unknown operator tag: {}", tag)));
+    }
+    OPERATOR_TAGS.remove(deps.storage, &tag);
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_operator_tag")
+        .add_attribute("tag", &tag)
+        .add_event(Event::new("thrive_remove_operator_tag").add_attribute("tag", tag)))
+}
+
+pub fn execute_register_reason_code(
+    deps: DepsMut,
+    info: MessageInfo,
+    code: String,
+    description: String,
+    default_amount: Option<Uint128>,
+    max_amount: Option<Uint128>,
+) -> StdResult<Response> {
+    validate_owner(deps.as_ref(), &info)?;
+
+    if code.is_empty() || code.len() > MAX_REASON_CODE_LENGTH {
+        return Err(StdError::generic_err(format!(
+            "Reason code must be between 1 and {} characters", MAX_REASON_CODE_LENGTH
+        )));
+    }
+    if description.len() > MAX_REASON_LENGTH {
+        return Err(StdError::generic_err(format!("Description exceeds maximum length of {}", MAX_REASON_LENGTH)));
+    }
+    if let (Some(default_amount), Some(max_amount)) = (default_amount, max_amount) {
+        if default_amount > max_amount {
+            return Err(StdError::generic_err("Default amount cannot exceed max amount"));
+        }
+    }
+
+    let old = REASON_CODES.may_load(deps.storage, &code)?;
+    REASON_CODES.save(deps.storage, &code, &ReasonCode {
+        code: code.clone(),
+        description: description.clone(),
+        default_amount,
+        max_amount,
+    })?;
+
+    let mut event = Event::new("thrive_register_reason_code")
+        .add_attribute("code", &code)
+        .add_attribute("description", description);
+    if let Some(old) = old {
+        event = event
+            .add_attribute("old_description", old.description)
+            .add_attribute("old_default_amount", old.default_amount.map(|a| a.to_string()).unwrap_or_default())
+            .add_attribute("old_max_amount", old.max_amount.map(|a| a.to_string()).unwrap_or_default());
+    }
+    if let Some(default_amount) = default_amount {
+        event = event.add_attribute("default_amount", default_amount.to_string());
+    }
+    if let Some(max_amount) = max_amount {
+        event = event.add_attribute("max_amount", max_amount.to_string());
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "register_reason_code")
+        .add_attribute("code", code)
+        .add_event(event))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{attr, coins, OverflowError, OverflowOperation};
+    use cosmwasm_std::Uint128;
+
+    const OWNER: &str = "owner";
+    const USER: &str = "user";
+    const DENOM: &str = "utoken";
+
+    fn reward_entry(recipient: &str, amount: u128, reason: &str) -> RewardEntry {
+        RewardEntry {
+            recipient: recipient.to_string(),
+            amount: Uint128::new(amount),
+            reason: reason.to_string(),
+            id: None,
+            campaign_id: None,
+            auto_payout: None,
+            reason_code: None,
+            memo: None,
+        }
+    }
+
+    fn reward_entry_msg(entry: RewardEntry, operator_tag: Option<String>) -> ExecuteMsg {
+        ExecuteMsg::Reward {
+            recipient: entry.recipient,
+            amount: entry.amount,
+            reason: entry.reason,
+            reward_id: entry.id,
+            campaign_id: entry.campaign_id,
+            auto_payout: entry.auto_payout,
+            operator_tag,
+            reason_code: entry.reason_code,
+            memo: entry.memo,
+        }
+    }
+
+    fn reward_msg(recipient: &str, amount: u128, reason: &str) -> ExecuteMsg {
+        reward_entry_msg(reward_entry(recipient, amount, reason), None)
+    }
+
+    #[test]
+    fn proper_initialization() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+
+        let res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "instantiate"),
+                attr("owner", OWNER),
+                attr("token_denom", DENOM),
+            ]
+        );
+
+        let state = STATE.load(&deps.storage).unwrap();
+        assert_eq!(state.owner, Addr::unchecked(OWNER));
+
+        let token_denom = TOKEN_DENOM.load(&deps.storage).unwrap();
+        assert_eq!(token_denom, DENOM.to_string());
+    }
+
+    #[test]
+    fn deposit_works() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let deposit_info = mock_info(USER, &coins(100, DENOM));
+        let msg = ExecuteMsg::Deposit {};
+        let res = execute(deps.as_mut(), mock_env(), deposit_info.clone(), msg).unwrap();
+
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "deposit"),
+                attr("sender", USER),
+                attr("amount", "100"),
+            ]
+        );
+
+        let balance = BALANCES.load(&deps.storage, &Addr::unchecked(USER)).unwrap();
+        assert_eq!(balance, Uint128::new(100));
+    }
+
+    #[test]
+    fn deposit_fails_for_zero_amount() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let deposit_info = mock_info(USER, &coins(0, DENOM));
+        let msg = ExecuteMsg::Deposit {};
+        let err = execute(deps.as_mut(), mock_env(), deposit_info, msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Deposit amount must be greater than zero"));
+    }
+
+    #[test]
+    fn withdraw_works() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let deposit_info = mock_info(USER, &coins(200, DENOM));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            deposit_info.clone(),
+            ExecuteMsg::Deposit {},
+        )
+        .unwrap();
+
+        let withdraw_msg = ExecuteMsg::Withdraw {
+            amount: Uint128::new(100),
+        };
+        let res = execute(deps.as_mut(), mock_env(), deposit_info.clone(), withdraw_msg).unwrap();
+
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "withdraw"), attr("amount", "100")]
+        );
+
+        let balance = BALANCES.load(&deps.storage, &Addr::unchecked(USER)).unwrap();
+        assert_eq!(balance, Uint128::new(100));
+    }
+
+    #[test]
+    fn withdraw_fails_for_zero_amount() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let withdraw_msg = ExecuteMsg::Withdraw {
+            amount: Uint128::zero(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), withdraw_msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Withdraw amount must be greater than zero"));
+    }
+
+    #[test]
+    fn withdraw_fails_for_insufficient_balance() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let withdraw_msg = ExecuteMsg::Withdraw {
+            amount: Uint128::new(100),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), withdraw_msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Insufficient balance"));
+    }
+
+    #[test]
+    fn reward_bulk_fails_for_mismatched_lengths() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::RewardBulk {
+            recipients: vec![USER.to_string()],
+            amounts: vec![Uint128::new(100), Uint128::new(50)], // Mismatched length
+            reasons: vec!["Reason1".to_string()],
+            reward_ids: None,
+            skip_invalid: false,
+            auto_payout: None,
+            operator_tag: None,
+        };
+
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Array lengths mismatch"));
+    }
+
+    #[test]
+    fn reward_works() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = reward_msg(USER, 50, "Test reward");
+
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "reward"),
+                attr("recipient", USER),
+                attr("amount", "50"),
+                attr("reason", "Test reward"),
+            ]
+        );
+
+        let balance = BALANCES.load(&deps.storage, &Addr::unchecked(USER)).unwrap();
+        assert_eq!(balance, Uint128::new(50));
+    }
+
+    #[test]
+    fn reward_bulk_works() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    
+        let msg = ExecuteMsg::RewardBulk {
+            recipients: vec![USER.to_string(), "user2".to_string()],
+            amounts: vec![Uint128::new(100), Uint128::new(50)],
+            reasons: vec!["Reason1".to_string(), "Reason2".to_string()],
+            reward_ids: None,
+            skip_invalid: false,
+            auto_payout: None,
+            operator_tag: None,
+        };
+    
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        assert_eq!(res.attributes, vec![attr("action", "reward_bulk")]);
+    
+        let balance1 = BALANCES.load(&deps.storage, &Addr::unchecked(USER)).unwrap();
+        assert_eq!(balance1, Uint128::new(100));
+    
+        let balance2 = BALANCES.load(&deps.storage, &Addr::unchecked("user2")).unwrap();
+        assert_eq!(balance2, Uint128::new(50));
+    }
+
+    #[test]
+    fn query_balance_works() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let deposit_info = mock_info(USER, &coins(100, DENOM));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            deposit_info.clone(),
+            ExecuteMsg::Deposit {},
+        )
+        .unwrap();
+
+        let balance = query_balance(deps.as_ref(), USER.to_string()).unwrap();
+        assert_eq!(balance, Uint128::new(100));
+    }
+
+    #[test]
+    fn update_ownership_works() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let new_owner = "new_owner";
+        let msg = ExecuteMsg::UpdateOwnership {
+            new_owner: new_owner.to_string(),
+        };
+
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "update_ownership"),
+                attr("new_owner", new_owner),
+            ]
+        );
+
+        let state = STATE.load(&deps.storage).unwrap();
+        assert_eq!(state.owner, Addr::unchecked(new_owner));
+    }
+
+    #[test]
+    fn set_token_denom_works() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let new_denom = "utest";
+        let res = set_token_denom(deps.as_mut(), info.clone(), new_denom.to_string()).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "set_token_denom"),
+                attr("denom", new_denom),
+            ]
+        );
+
+        let token_denom = TOKEN_DENOM.load(&deps.storage).unwrap();
+        assert_eq!(token_denom, new_denom);
+    }
+
+    #[test]
+    fn set_token_denom_fails_for_unauthorized() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let unauthorized_info = mock_info(USER, &[]);
+        let new_denom = "utest";
+        let err = set_token_denom(deps.as_mut(), unauthorized_info, new_denom.to_string()).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Unauthorized: Only the owner can call this"));
+    }
+
+    #[test]
+    fn query_token_denom_works() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let token_denom = query_token_denom(deps.as_ref()).unwrap();
+        assert_eq!(token_denom, DENOM);
+    }
+
+    #[test]
+    fn validate_owner_works() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let unauthorized_info = mock_info(USER, &[]);
+        let err = validate_owner(deps.as_ref(), &unauthorized_info).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Unauthorized: Only the owner can call this"));
+    }
+
+    #[test]
+    fn reward_bulk_emits_event_per_recipient() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::RewardBulk {
+            recipients: vec![USER.to_string(), "user2".to_string()],
+            amounts: vec![Uint128::new(100), Uint128::new(50)],
+            reasons: vec!["Reason1".to_string(), "Reason2".to_string()],
+            reward_ids: None,
+            skip_invalid: false,
+            auto_payout: None,
+            operator_tag: None,
+        };
+
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.events,
+            vec![
+                Event::new("thrive_reward")
+                    .add_attribute("reward_id", "1")
+                    .add_attribute("recipient", USER)
+                    .add_attribute("amount", "100")
+                    .add_attribute("reason", "Reason1")
+                    .add_attribute("balance", "100"),
+                Event::new("thrive_reward")
+                    .add_attribute("reward_id", "2")
+                    .add_attribute("recipient", "user2")
+                    .add_attribute("amount", "50")
+                    .add_attribute("reason", "Reason2")
+                    .add_attribute("balance", "50"),
+            ]
+        );
+    }
+
+    #[test]
+    fn deposit_and_withdraw_emit_resulting_balance() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let deposit_info = mock_info(USER, &coins(200, DENOM));
+        let res = execute(deps.as_mut(), mock_env(), deposit_info.clone(), ExecuteMsg::Deposit {}).unwrap();
+        assert_eq!(
+            res.events,
+            vec![Event::new("thrive_deposit")
+                .add_attribute("sender", USER)
+                .add_attribute("amount", "200")
+                .add_attribute("balance", "200")]
+        );
+
+        let withdraw_msg = ExecuteMsg::Withdraw {
+            amount: Uint128::new(50),
+        };
+        let res = execute(deps.as_mut(), mock_env(), deposit_info, withdraw_msg).unwrap();
+        assert_eq!(
+            res.events,
+            vec![
+                Event::new("Withdrawal")
+                    .add_attribute("sender", USER)
+                    .add_attribute("amount", "50"),
+                Event::new("thrive_withdraw")
+                    .add_attribute("sender", USER)
+                    .add_attribute("amount", "50")
+                    .add_attribute("fee", "0")
+                    .add_attribute("payout", "50")
+                    .add_attribute("balance", "150"),
+            ]
+        );
+    }
+
+    #[test]
+    fn admin_events_record_old_and_new_values() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let res = set_token_denom(deps.as_mut(), info.clone(), "utest".to_string()).unwrap();
+        assert_eq!(
+            res.events,
+            vec![Event::new("thrive_set_token_denom")
+                .add_attribute("old_denom", DENOM)
+                .add_attribute("new_denom", "utest")]
+        );
+
+        let msg = ExecuteMsg::SetIbcChannel { channel_id: "channel-0".to_string(), allowed: true };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.events,
+            vec![Event::new("thrive_set_ibc_channel")
+                .add_attribute("channel_id", "channel-0")
+                .add_attribute("old_allowed", "false")
+                .add_attribute("new_allowed", "true")]
+        );
+
+        let fee_grant_config = |spend_limit: u128| ExecuteMsg::SetFeeGrantConfig {
+            denom: "uxion".to_string(),
+            spend_limit: Uint128::new(spend_limit),
+            expiration_seconds: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(OWNER, &coins(1000, "uxion")), fee_grant_config(100)).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), fee_grant_config(40)).unwrap();
+        let event = &res.events[0];
+        assert!(event.attributes.contains(&attr("old_spend_limit", "100")));
+        assert!(event.attributes.contains(&attr("new_spend_limit", "40")));
+        assert!(event.attributes.contains(&attr("old_budget", "1000")));
+        assert!(event.attributes.contains(&attr("new_budget", "1000")));
+
+        let block = |freeze: bool| ExecuteMsg::BlockAddress { address: USER.to_string(), freeze };
+        execute(deps.as_mut(), mock_env(), info.clone(), block(false)).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), block(true)).unwrap();
+        let event = &res.events[0];
+        assert!(event.attributes.contains(&attr("old_blocked", "true")));
+        assert!(event.attributes.contains(&attr("old_freeze", "false")));
+        assert!(event.attributes.contains(&attr("freeze", "true")));
+
+        let tag = |grantee: &str| ExecuteMsg::RegisterOperatorTag { tag: "ops".to_string(), grantee: grantee.to_string() };
+        execute(deps.as_mut(), mock_env(), info.clone(), tag("first")).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), tag("second")).unwrap();
+        let event = &res.events[0];
+        assert!(event.attributes.contains(&attr("old_grantee", "first")));
+        assert!(event.attributes.contains(&attr("grantee", "second")));
+
+        let msg = ExecuteMsg::UpdateOwnership {
+            new_owner: "new_owner".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.events,
+            vec![Event::new("thrive_update_ownership")
+                .add_attribute("old_owner", OWNER)
+                .add_attribute("new_owner", "new_owner")]
+        );
+    }
+
+    #[test]
+    fn reward_rejects_processed_reward_id() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = reward_entry_msg(
+            RewardEntry { id: Some("payout-1".to_string()), ..reward_entry(USER, 50, "Test reward") },
+            None,
+        );
+        execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Reward id already processed: payout-1"));
+
+        let balance = BALANCES.load(&deps.storage, &Addr::unchecked(USER)).unwrap();
+        assert_eq!(balance, Uint128::new(50));
+        assert_eq!(query_processed_reward(deps.as_ref(), "payout-1".to_string()).unwrap(), Some(1));
+    }
+
+    #[test]
+    fn reward_bulk_skips_processed_reward_ids() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let first = ExecuteMsg::RewardBulk {
+            recipients: vec![USER.to_string()],
+            amounts: vec![Uint128::new(100)],
+            reasons: vec!["Reason1".to_string()],
+            reward_ids: Some(vec!["a".to_string()]),
+            skip_invalid: false,
+            auto_payout: None,
+            operator_tag: None,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), first).unwrap();
+
+        let retry = ExecuteMsg::RewardBulk {
+            recipients: vec![USER.to_string(), "user2".to_string()],
+            amounts: vec![Uint128::new(100), Uint128::new(50)],
+            reasons: vec!["Reason1".to_string(), "Reason2".to_string()],
+            reward_ids: Some(vec!["a".to_string(), "b".to_string()]),
+            skip_invalid: false,
+            auto_payout: None,
+            operator_tag: None,
+        };
+        // Outsiders are refused even when every entry is a duplicate or the batch is empty.
+        let outsider = mock_info("outsider", &[]);
+        let err = execute(deps.as_mut(), mock_env(), outsider.clone(), retry.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Unauthorized: Only the owner can call this"));
+        let empty = ExecuteMsg::RewardBatch { entries: vec![], skip_invalid: false, operator_tag: None };
+        let err = execute(deps.as_mut(), mock_env(), outsider, empty).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Unauthorized: Only the owner can call this"));
+
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), retry).unwrap();
+        assert_eq!(
+            res.events[0],
+            Event::new("thrive_reward_duplicate")
+                .add_attribute("client_reward_id", "a")
+                .add_attribute("reward_id", "1")
+                .add_attribute("recipient", USER)
+        );
+
+        let balance1 = BALANCES.load(&deps.storage, &Addr::unchecked(USER)).unwrap();
+        assert_eq!(balance1, Uint128::new(100));
+        let balance2 = BALANCES.load(&deps.storage, &Addr::unchecked("user2")).unwrap();
+        assert_eq!(balance2, Uint128::new(50));
+    }
+
+    #[test]
+    fn reward_batch_aggregates_recipients_and_reports_outcomes() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let entry = |recipient: &str, amount: u128, id: &str| RewardEntry {
+            id: Some(id.to_string()),
+            ..reward_entry(recipient, amount, "Batch")
+        };
+        let msg = ExecuteMsg::RewardBatch {
+            entries: vec![entry(USER, 10, "a"), entry("user2", 20, "b"), entry(USER, 30, "c"), entry(USER, 40, "a")],
+            skip_invalid: false,
+            operator_tag: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let response: RewardBatchResponse = cosmwasm_std::from_json(res.data.unwrap()).unwrap();
+        let statuses: Vec<_> = response.results.iter().map(|r| (r.index, r.status.clone(), r.reward_id)).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (0, RewardEntryStatus::Credited, Some(1)),
+                (1, RewardEntryStatus::Credited, Some(2)),
+                (2, RewardEntryStatus::Credited, Some(3)),
+                (3, RewardEntryStatus::Duplicate, Some(1)),
+            ]
+        );
+        assert_eq!(res.events[2].attributes[4], attr("balance", "40"));
+
+        let balance1 = BALANCES.load(&deps.storage, &Addr::unchecked(USER)).unwrap();
+        assert_eq!(balance1, Uint128::new(40));
+        let balance2 = BALANCES.load(&deps.storage, &Addr::unchecked("user2")).unwrap();
+        assert_eq!(balance2, Uint128::new(20));
+    }
+
+    #[test]
+    fn reward_batch_enforces_max_batch_size() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::SetMaxBatchSize { max_batch_size: 1 }).unwrap();
+        assert_eq!(query_max_batch_size(deps.as_ref()).unwrap(), 1);
+
+        let entry = reward_entry(USER, 10, "Batch");
+        let msg = ExecuteMsg::RewardBatch {
+            entries: vec![entry.clone(), entry],
+            skip_invalid: false,
+            operator_tag: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Batch size exceeds maximum of 1"));
+    }
+
+    #[test]
+    fn reward_bulk_skips_invalid_recipients_when_requested() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::RewardBulk {
+            recipients: vec![USER.to_string(), "".to_string()],
+            amounts: vec![Uint128::new(100), Uint128::new(50)],
+            reasons: vec!["Reason1".to_string(), "Reason2".to_string()],
+            reward_ids: None,
+            skip_invalid: false,
+            auto_payout: None,
+            operator_tag: None,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+
+        let msg = ExecuteMsg::RewardBulk {
+            recipients: vec![USER.to_string(), "".to_string()],
+            amounts: vec![Uint128::new(100), Uint128::new(50)],
+            reasons: vec!["Reason1".to_string(), "Reason2".to_string()],
+            reward_ids: None,
+            skip_invalid: true,
+            auto_payout: None,
+            operator_tag: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let response: RewardBatchResponse = cosmwasm_std::from_json(res.data.unwrap()).unwrap();
+        assert_eq!(response.results[0].status, RewardEntryStatus::Credited);
+        assert_eq!(response.results[1].status, RewardEntryStatus::Invalid);
+        assert_eq!(response.results[1].index, 1);
+        assert!(response.results[1].error.is_some());
+        assert_eq!(res.events[1].ty, "thrive_reward_invalid");
+
+        let balance = BALANCES.load(&deps.storage, &Addr::unchecked(USER)).unwrap();
+        assert_eq!(balance, Uint128::new(100));
+    }
+
+    #[test]
+    fn scheduled_reward_is_credited_after_release_time() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let fund_info = mock_info(OWNER, &coins(100, DENOM));
+        execute(deps.as_mut(), mock_env(), fund_info, ExecuteMsg::FundPool {}).unwrap();
+
+        let release_at = mock_env().block.time.plus_seconds(3600);
+        let msg = ExecuteMsg::ScheduleReward {
+            recipient: USER.to_string(),
+            amount: Uint128::new(60),
+            reason: "Hackathon".to_string(),
+            release_at,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        assert_eq!(query_reward_pool(deps.as_ref()).unwrap(), Uint128::new(40));
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::zero());
+
+        let pending = query_scheduled_rewards(deps.as_ref(), USER.to_string(), None, None).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].release_at, release_at);
+
+        let withdraw_msg = ExecuteMsg::Withdraw {
+            amount: Uint128::new(60),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), withdraw_msg.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Insufficient balance"));
+
+        let mut env = mock_env();
+        env.block.time = release_at;
+        execute(deps.as_mut(), env, mock_info(USER, &[]), withdraw_msg).unwrap();
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::zero());
+        assert!(query_scheduled_rewards(deps.as_ref(), USER.to_string(), None, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn long_reasons_are_rejected_when_rewards_are_committed() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let fund_info = mock_info(OWNER, &coins(100, DENOM));
+        execute(deps.as_mut(), mock_env(), fund_info, ExecuteMsg::FundPool {}).unwrap();
+
+        let now = mock_env().block.time;
+        let long_reason = "x".repeat(MAX_REASON_LENGTH + 1);
+        let msg = ExecuteMsg::ScheduleReward {
+            recipient: USER.to_string(),
+            amount: Uint128::new(60),
+            reason: long_reason.clone(),
+            release_at: now.plus_seconds(3600),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Reason exceeds maximum length of 256"));
+        let msg = ExecuteMsg::CreateStream {
+            recipient: USER.to_string(),
+            rate_per_second: Uint128::new(1),
+            start: now,
+            end: now.plus_seconds(10),
+            reason: long_reason.clone(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Reason exceeds maximum length of 256"));
+
+        // A committed reward is credited whatever its reason, so it can't block withdrawals.
+        scheduled_rewards().save(&mut deps.storage, 1, &ScheduledReward {
+            id: 1,
+            recipient: Addr::unchecked(USER),
+            amount: Uint128::new(60),
+            reason: long_reason,
+            release_at: now,
+        }).unwrap();
+        let withdraw = ExecuteMsg::Withdraw { amount: Uint128::new(60) };
+        execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), withdraw).unwrap();
+    }
+
+    #[test]
+    fn cancel_scheduled_reward_returns_funds_to_pool() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let release_at = mock_env().block.time.plus_seconds(3600);
+        let msg = ExecuteMsg::ScheduleReward {
+            recipient: USER.to_string(),
+            amount: Uint128::new(60),
+            reason: "Hackathon".to_string(),
+            release_at,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Insufficient reward pool"));
+
+        let fund_info = mock_info(OWNER, &coins(100, DENOM));
+        execute(deps.as_mut(), mock_env(), fund_info, ExecuteMsg::FundPool {}).unwrap();
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = release_at;
+        let err = execute(deps.as_mut(), env, info.clone(), ExecuteMsg::CancelScheduledReward { id: 1 }).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Scheduled reward already released"));
+
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::CancelScheduledReward { id: 1 }).unwrap();
+        assert_eq!(query_reward_pool(deps.as_ref()).unwrap(), Uint128::new(100));
+        assert!(query_scheduled_rewards(deps.as_ref(), USER.to_string(), None, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn stream_accrues_per_second_and_settles_on_withdraw() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let fund_info = mock_info(OWNER, &coins(1000, DENOM));
+        execute(deps.as_mut(), mock_env(), fund_info, ExecuteMsg::FundPool {}).unwrap();
+
+        let start = mock_env().block.time;
+        let msg = ExecuteMsg::CreateStream {
+            recipient: USER.to_string(),
+            rate_per_second: Uint128::new(2),
+            start,
+            end: start.plus_seconds(100),
+            reason: "Moderation".to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        assert_eq!(query_reward_pool(deps.as_ref()).unwrap(), Uint128::new(800));
+
+        let mut env = mock_env();
+        env.block.time = start.plus_seconds(30);
+        let streams = query_streams(deps.as_ref(), env.clone(), USER.to_string(), None, None).unwrap();
+        assert_eq!(streams[0].withdrawable, Uint128::new(60));
+
+        let withdraw_msg = ExecuteMsg::Withdraw {
+            amount: Uint128::new(50),
+        };
+        execute(deps.as_mut(), env.clone(), mock_info(USER, &[]), withdraw_msg).unwrap();
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::new(10));
+
+        execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::PauseStream { id: 1 }).unwrap();
+        env.block.time = start.plus_seconds(50);
+        let streams = query_streams(deps.as_ref(), env.clone(), USER.to_string(), None, None).unwrap();
+        assert_eq!(streams[0].withdrawable, Uint128::zero());
+
+        execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::ResumeStream { id: 1 }).unwrap();
+        env.block.time = start.plus_seconds(60);
+        let streams = query_streams(deps.as_ref(), env.clone(), USER.to_string(), None, None).unwrap();
+        assert_eq!(streams[0].withdrawable, Uint128::new(20));
+        assert_eq!(streams[0].stream.end, start.plus_seconds(120));
+    }
+
+    #[test]
+    fn stream_rejects_past_start_and_ignores_pause_before_start() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let fund_info = mock_info(OWNER, &coins(1000, DENOM));
+        execute(deps.as_mut(), mock_env(), fund_info, ExecuteMsg::FundPool {}).unwrap();
+
+        let now = mock_env().block.time;
+        let stream = |start: Timestamp| ExecuteMsg::CreateStream {
+            recipient: USER.to_string(),
+            rate_per_second: Uint128::new(2),
+            start,
+            end: start.plus_seconds(100),
+            reason: "Moderation".to_string(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), stream(now.minus_seconds(10))).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Stream start must not be in the past"));
+
+        let start = now.plus_seconds(50);
+        execute(deps.as_mut(), mock_env(), info.clone(), stream(start)).unwrap();
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::PauseStream { id: 1 }).unwrap();
+
+        // Paused 50s before the start and 20s after it: only the running time shifts the schedule.
+        let mut env = mock_env();
+        env.block.time = start.plus_seconds(20);
+        execute(deps.as_mut(), env.clone(), info, ExecuteMsg::ResumeStream { id: 1 }).unwrap();
+        let streams = query_streams(deps.as_ref(), env, USER.to_string(), None, None).unwrap();
+        assert_eq!(streams[0].stream.start, start.plus_seconds(20));
+        assert_eq!(streams[0].stream.end, start.plus_seconds(120));
+        assert_eq!(streams[0].withdrawable, Uint128::zero());
+    }
+
+    #[test]
+    fn cancel_stream_credits_accrued_and_refunds_pool() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let fund_info = mock_info(OWNER, &coins(1000, DENOM));
+        execute(deps.as_mut(), mock_env(), fund_info, ExecuteMsg::FundPool {}).unwrap();
+
+        let start = mock_env().block.time;
+        let msg = ExecuteMsg::CreateStream {
+            recipient: USER.to_string(),
+            rate_per_second: Uint128::new(2),
+            start,
+            end: start.plus_seconds(100),
+            reason: "Moderation".to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = start.plus_seconds(25);
+        execute(deps.as_mut(), env.clone(), mock_info(USER, &[]), ExecuteMsg::CancelStream { id: 1 }).unwrap_err();
+        execute(deps.as_mut(), env.clone(), info, ExecuteMsg::CancelStream { id: 1 }).unwrap();
+
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::new(50));
+        assert_eq!(query_reward_pool(deps.as_ref()).unwrap(), Uint128::new(950));
+        assert!(query_streams(deps.as_ref(), env, USER.to_string(), None, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn campaign_manager_rewards_within_budget() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let now = mock_env().block.time;
+        let msg = ExecuteMsg::CreateCampaign {
+            name: "Gitcoin round 4".to_string(),
+            budget: Uint128::new(100),
+            denom: DENOM.to_string(),
+            start: now,
+            end: now.plus_seconds(86400),
+            managers: vec!["manager".to_string()],
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let reward = |amount: u128, campaign_id: Option<u64>| {
+            reward_entry_msg(RewardEntry { campaign_id, ..reward_entry(USER, amount, "Grant") }, None)
+        };
+        let manager_info = mock_info("manager", &[]);
+        execute(deps.as_mut(), mock_env(), manager_info.clone(), reward(70, Some(1))).unwrap();
+
+        let err = execute(deps.as_mut(), mock_env(), manager_info.clone(), reward(40, Some(1))).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Campaign 1 budget exceeded"));
+
+        let err = execute(deps.as_mut(), mock_env(), manager_info, reward(10, None)).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Unauthorized: Only the owner can call this"));
+
+        let mut env = mock_env();
+        env.block.time = now.plus_seconds(86400);
+        let err = execute(deps.as_mut(), env, info, reward(10, Some(1))).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Campaign 1 is not active"));
+
+        let campaign = query_campaign(deps.as_ref(), 1).unwrap();
+        assert_eq!(campaign.campaign.spent, Uint128::new(70));
+        assert_eq!(campaign.remaining, Uint128::new(30));
+        assert_eq!(query_campaigns(deps.as_ref(), None, None).unwrap(), vec![campaign]);
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::new(70));
+    }
+
+    #[test]
+    fn delegate_rewards_within_quota_and_prefix() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::SetDelegate {
+            address: "lead".to_string(),
+            quota: Uint128::new(100),
+            period_seconds: 86400,
+            expires_at: None,
+            reason_prefix: Some("discord:".to_string()),
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+
+        let reward = |amount: u128, reason: &str| reward_msg(USER, amount, reason);
+        let lead_info = mock_info("lead", &[]);
+        execute(deps.as_mut(), mock_env(), lead_info.clone(), reward(60, "discord:help")).unwrap();
+
+        let err = execute(deps.as_mut(), mock_env(), lead_info.clone(), reward(10, "github:pr")).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Delegate may only reward reasons starting with discord:"));
+
+        let err = execute(deps.as_mut(), mock_env(), lead_info.clone(), reward(50, "discord:help")).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Delegate quota exceeded"));
+
+        let delegate = query_delegate(deps.as_ref(), mock_env(), "lead".to_string()).unwrap();
+        assert_eq!(delegate.remaining_quota, Uint128::new(40));
+
+        // Re-setting the delegate keeps what it already used this period.
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let delegate = query_delegate(deps.as_ref(), mock_env(), "lead".to_string()).unwrap();
+        assert_eq!(delegate.remaining_quota, Uint128::new(40));
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(86400);
+        let delegate = query_delegate(deps.as_ref(), env.clone(), "lead".to_string()).unwrap();
+        assert_eq!(delegate.remaining_quota, Uint128::new(100));
+        execute(deps.as_mut(), env, lead_info, reward(50, "discord:help")).unwrap();
+
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::new(110));
+    }
+
+    #[test]
+    fn expired_delegate_cannot_reward() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::SetDelegate {
+            address: "lead".to_string(),
+            quota: Uint128::new(100),
+            period_seconds: 86400,
+            expires_at: Some(mock_env().block.time.plus_seconds(60)),
+            reason_prefix: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(60);
+        let msg = reward_msg(USER, 10, "Help");
+        let err = execute(deps.as_mut(), env, mock_info("lead", &[]), msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Delegate expired"));
+    }
+
+    #[test]
+    fn large_rewards_require_threshold_approval() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::SetApprovalConfig {
+            approvers: vec!["alice".to_string(), "bob".to_string(), "carol".to_string()],
+            threshold: 2,
+            amount_threshold: Uint128::new(1000),
+            proposal_ttl_seconds: 3600,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = reward_msg(USER, 5000, "Grant");
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Reward amount exceeds approval threshold, submit a proposal"));
+
+        let msg = ExecuteMsg::ProposeReward {
+            recipient: USER.to_string(),
+            amount: Uint128::new(5000),
+            reason: "Grant".to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::ApproveReward { id: 1 })
+            .unwrap_err();
+        assert_eq!(err, StdError::generic_err("Proposal already approved by sender"));
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::zero());
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), ExecuteMsg::ApproveReward { id: 1 }).unwrap();
+        assert_eq!(res.events[1].ty, "thrive_reward");
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::new(5000));
+        assert_eq!(load_proposal(&deps.storage, 1).unwrap().status, ProposalStatus::Executed);
+    }
+
+    #[test]
+    fn expired_proposal_cannot_be_approved() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::SetApprovalConfig {
+            approvers: vec!["alice".to_string(), "bob".to_string()],
+            threshold: 2,
+            amount_threshold: Uint128::new(1000),
+            proposal_ttl_seconds: 3600,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::ProposeReward {
+            recipient: USER.to_string(),
+            amount: Uint128::new(5000),
+            reason: "Grant".to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::ApproveReward { id: 1 }).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(3600);
+        let err = execute(deps.as_mut(), env, mock_info("bob", &[]), ExecuteMsg::ApproveReward { id: 1 }).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Proposal expired"));
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::zero());
+    }
+
+    #[test]
+    fn deposit_overflow_returns_error() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        BALANCES.save(deps.as_mut().storage, &Addr::unchecked(USER), &Uint128::MAX, 0).unwrap();
+
+        let deposit_info = mock_info(USER, &coins(1, DENOM));
+        let err = execute(deps.as_mut(), mock_env(), deposit_info, ExecuteMsg::Deposit {}).unwrap_err();
+        assert_eq!(
+            err,
+            StdError::overflow(OverflowError::new(OverflowOperation::Add, Uint128::MAX, Uint128::one()))
+        );
+    }
+
+    #[test]
+    fn reward_overflow_returns_error() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let reward = |amount: Uint128| reward_msg(USER, amount.u128(), "Overflow");
+        execute(deps.as_mut(), mock_env(), info.clone(), reward(Uint128::MAX - Uint128::one())).unwrap();
+
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), reward(Uint128::new(2))).unwrap_err();
+        assert!(matches!(err, StdError::Overflow { .. }));
+
+        execute(deps.as_mut(), mock_env(), info, reward(Uint128::one())).unwrap();
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::MAX);
+    }
+
+    #[test]
+    fn fund_pool_overflow_returns_error() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        REWARD_POOL.save(deps.as_mut().storage, &Uint128::MAX).unwrap();
+
+        let fund_info = mock_info(OWNER, &coins(1, DENOM));
+        let err = execute(deps.as_mut(), mock_env(), fund_info, ExecuteMsg::FundPool {}).unwrap_err();
+        assert!(matches!(err, StdError::Overflow { .. }));
+    }
+
+    #[test]
+    fn balance_and_total_snapshots_by_height() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let env_at = |height: u64| {
+            let mut env = mock_env();
+            env.block.height = height;
+            env
+        };
+        execute(deps.as_mut(), env_at(1), info.clone(), ExecuteMsg::SetSnapshotInterval { interval: 1 }).unwrap();
+        let reward = |amount: u128| reward_msg(USER, amount, "Snapshot");
+        execute(deps.as_mut(), env_at(100), info.clone(), reward(10)).unwrap();
+        execute(deps.as_mut(), env_at(105), info.clone(), reward(20)).unwrap();
+        let deposit_info = mock_info("user2", &coins(5, DENOM));
+        execute(deps.as_mut(), env_at(105), deposit_info, ExecuteMsg::Deposit {}).unwrap();
+
+        let at = |deps: Deps, height: u64| query_balance_at(deps, env_at(200), USER.to_string(), height).unwrap().amount;
+        assert_eq!(at(deps.as_ref(), 100), Uint128::zero());
+        assert_eq!(at(deps.as_ref(), 101), Uint128::new(10));
+        assert_eq!(at(deps.as_ref(), 105), Uint128::new(10));
+        assert_eq!(at(deps.as_ref(), 106), Uint128::new(30));
+        assert_eq!(query_total_at(deps.as_ref(), env_at(200), 103).unwrap().amount, Uint128::new(10));
+        assert_eq!(query_total_at(deps.as_ref(), env_at(200), 150).unwrap().amount, Uint128::new(35));
+
+        let err = query_balance_at(deps.as_ref(), env_at(200), USER.to_string(), 201).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Height is in the future"));
+    }
+
+    #[test]
+    fn snapshot_interval_bounds_checkpoints() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let env_at = |height: u64| {
+            let mut env = mock_env();
+            env.block.height = height;
+            env
+        };
+        execute(deps.as_mut(), env_at(100), info.clone(), ExecuteMsg::SetSnapshotInterval { interval: 10 }).unwrap();
+
+        let reward = |amount: u128| reward_msg(USER, amount, "Snapshot");
+        execute(deps.as_mut(), env_at(112), info.clone(), reward(10)).unwrap();
+        execute(deps.as_mut(), env_at(115), info.clone(), reward(20)).unwrap();
+        execute(deps.as_mut(), env_at(121), info.clone(), reward(40)).unwrap();
+
+        let checkpoints: Vec<u64> = BALANCE_CHECKPOINTS
+            .keys(&deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<_>>()
+            .unwrap();
+        assert_eq!(checkpoints, vec![110, 120]);
+
+        let res = query_balance_at(deps.as_ref(), env_at(200), USER.to_string(), 119).unwrap();
+        assert_eq!(res, BalanceAtResponse { height: 110, amount: Uint128::zero() });
+        let res = query_balance_at(deps.as_ref(), env_at(200), USER.to_string(), 125).unwrap();
+        assert_eq!(res, BalanceAtResponse { height: 120, amount: Uint128::new(30) });
+
+        execute(deps.as_mut(), env_at(130), info, ExecuteMsg::SetSnapshotInterval { interval: 0 }).unwrap();
+        let err = query_balance_at(deps.as_ref(), env_at(200), USER.to_string(), 150).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Balance snapshots were disabled at height 150"));
+    }
+
+    #[test]
+    fn withdraw_sends_fee_to_collector() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateFeeConfig {
+            fee_bps: 250,
+            min_fee: Some(Uint128::new(5)),
+            collector: "collector".to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let user_info = mock_info(USER, &coins(1_000, DENOM));
+        execute(deps.as_mut(), mock_env(), user_info.clone(), ExecuteMsg::Deposit {}).unwrap();
+
+        let withdraw = |amount: u128| ExecuteMsg::Withdraw { amount: Uint128::new(amount) };
+        let paid = || Reply {
+            id: WITHDRAW_PAYOUT_REPLY_ID,
+            result: SubMsgResult::Ok(cosmwasm_std::SubMsgResponse { events: vec![], data: None }),
+        };
+        let res = execute(deps.as_mut(), mock_env(), user_info.clone(), withdraw(400)).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::reply_always(
+                BankMsg::Send { to_address: USER.to_string(), amount: coins(390, DENOM) },
+                WITHDRAW_PAYOUT_REPLY_ID,
+            )]
+        );
+        let event = res.events.iter().find(|e| e.ty == "thrive_withdraw").unwrap();
+        assert!(event.attributes.contains(&attr("fee", "10")));
+        assert!(event.attributes.contains(&attr("payout", "390")));
+
+        // The fee is collected once the payout went through.
+        let res = reply(deps.as_mut(), mock_env(), paid()).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send { to_address: "collector".to_string(), amount: coins(10, DENOM) })]
+        );
+        assert!(PENDING_PAYOUT.may_load(&deps.storage).unwrap().is_none());
+
+        // The flat minimum applies to small withdrawals but stays under the hard cap.
+        execute(deps.as_mut(), mock_env(), user_info.clone(), withdraw(100)).unwrap();
+        let res = reply(deps.as_mut(), mock_env(), paid()).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address: "collector".to_string(), amount: coins(5, DENOM) })
+        );
+        execute(deps.as_mut(), mock_env(), user_info, withdraw(20)).unwrap();
+        let res = reply(deps.as_mut(), mock_env(), paid()).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address: "collector".to_string(), amount: coins(2, DENOM) })
+        );
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::new(480));
+    }
+
+    #[test]
+    fn update_fee_config_enforces_cap_and_owner() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = |fee_bps: u16| ExecuteMsg::UpdateFeeConfig {
+            fee_bps,
+            min_fee: None,
+            collector: "collector".to_string(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg(MAX_FEE_BPS + 1)).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Fee cannot exceed 1000 basis points"));
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), msg(100)).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Unauthorized: Only the owner can call this"));
+
+        execute(deps.as_mut(), mock_env(), info, msg(100)).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetFeeConfig {}).unwrap();
+        let config: Option<FeeConfig> = cosmwasm_std::from_json(res).unwrap();
+        assert_eq!(
+            config,
+            Some(FeeConfig { fee_bps: 100, min_fee: Uint128::zero(), collector: Addr::unchecked("collector") })
+        );
+    }
+
+    #[test]
+    fn withdraw_with_delay_unbonds_until_claimed() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::SetUnbondingDelay { delay_seconds: 100 }).unwrap();
+
+        let user_info = mock_info(USER, &coins(300, DENOM));
+        execute(deps.as_mut(), mock_env(), user_info.clone(), ExecuteMsg::Deposit {}).unwrap();
+
+        let withdraw = |amount: u128| ExecuteMsg::Withdraw { amount: Uint128::new(amount) };
+        let res = execute(deps.as_mut(), mock_env(), user_info.clone(), withdraw(100)).unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::new(200));
+
+        let mut later = mock_env();
+        later.block.time = later.block.time.plus_seconds(50);
+        execute(deps.as_mut(), later.clone(), user_info.clone(), withdraw(50)).unwrap();
+
+        let queue = query_unbonding(deps.as_ref(), USER.to_string(), None, None).unwrap();
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue[1].release_at, later.block.time.plus_seconds(100));
+
+        let err = execute(deps.as_mut(), mock_env(), user_info.clone(), ExecuteMsg::ClaimUnbonded {}).unwrap_err();
+        assert_eq!(err, StdError::generic_err("No unbonded funds to claim"));
+
+        let mut matured = mock_env();
+        matured.block.time = matured.block.time.plus_seconds(100);
+        let res = execute(deps.as_mut(), matured, user_info.clone(), ExecuteMsg::ClaimUnbonded {}).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address: USER.to_string(), amount: coins(100, DENOM) })
+        );
+        let queue = query_unbonding(deps.as_ref(), USER.to_string(), None, None).unwrap();
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].amount, Uint128::new(50));
+    }
+
+    #[test]
+    fn withdraw_enforces_minimum_except_full_balance() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateDustConfig {
+            min_withdraw: Uint128::new(50),
+            inactivity_seconds: 1_000,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let user_info = mock_info(USER, &coins(80, DENOM));
+        execute(deps.as_mut(), mock_env(), user_info.clone(), ExecuteMsg::Deposit {}).unwrap();
+
+        let withdraw = |amount: u128| ExecuteMsg::Withdraw { amount: Uint128::new(amount) };
+        let err = execute(deps.as_mut(), mock_env(), user_info.clone(), withdraw(40)).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Withdraw amount is below the minimum of 50"));
+
+        execute(deps.as_mut(), mock_env(), user_info.clone(), withdraw(50)).unwrap();
+        execute(deps.as_mut(), mock_env(), user_info, withdraw(30)).unwrap();
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::zero());
+    }
+
+    #[test]
+    fn sweep_dust_moves_inactive_balances_to_pool() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateDustConfig {
+            min_withdraw: Uint128::new(50),
+            inactivity_seconds: 1_000,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let reward = |recipient: &str, amount: u128| reward_msg(recipient, amount, "Dust");
+        execute(deps.as_mut(), mock_env(), info.clone(), reward("dusty", 5)).unwrap();
+        execute(deps.as_mut(), mock_env(), info.clone(), reward("frozen", 5)).unwrap();
+        execute(deps.as_mut(), mock_env(), info.clone(), reward("whale", 500)).unwrap();
+        let msg = ExecuteMsg::BlockAddress { address: "frozen".to_string(), freeze: true };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let mut later = mock_env();
+        later.block.time = later.block.time.plus_seconds(900);
+        execute(deps.as_mut(), later.clone(), info.clone(), reward("recent", 5)).unwrap();
+
+        let sweep = |threshold: u128| ExecuteMsg::SweepDust {
+            threshold: Uint128::new(threshold),
+            start_after: None,
+            limit: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), sweep(51)).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Dust threshold cannot exceed the minimum withdrawal of 50"));
+
+        later.block.time = later.block.time.plus_seconds(200);
+        let res = execute(deps.as_mut(), later, info, sweep(10)).unwrap();
+        assert_eq!(
+            res.events,
+            vec![Event::new("thrive_sweep_dust")
+                .add_attribute("address", "dusty")
+                .add_attribute("amount", "5")]
+        );
+        assert_eq!(query_balance(deps.as_ref(), "dusty".to_string()).unwrap(), Uint128::zero());
+        assert_eq!(query_balance(deps.as_ref(), "frozen".to_string()).unwrap(), Uint128::new(5));
+        assert_eq!(query_balance(deps.as_ref(), "recent".to_string()).unwrap(), Uint128::new(5));
+        assert_eq!(query_balance(deps.as_ref(), "whale".to_string()).unwrap(), Uint128::new(500));
+        assert_eq!(query_reward_pool(deps.as_ref()).unwrap(), Uint128::new(5));
+    }
+
+    #[test]
+    fn auto_payout_sends_rewards_to_wallets() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let reward = |recipient: &str, auto_payout: Option<bool>| {
+            reward_entry_msg(RewardEntry { auto_payout, ..reward_entry(recipient, 10, "Auto") }, None)
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), reward(USER, Some(true))).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address: USER.to_string(), amount: coins(10, DENOM) })
+        );
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::zero());
+
+        // The recipient's preference overrides both the reward option and the contract default.
+        execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), ExecuteMsg::SetPayoutPreference { auto: false })
+            .unwrap();
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::SetAutoPayout { auto: true }).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), reward(USER, Some(true))).unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::new(10));
+
+        let msg = ExecuteMsg::RewardBulk {
+            recipients: vec!["user2".to_string(), "user2".to_string(), USER.to_string()],
+            amounts: vec![Uint128::new(5), Uint128::new(7), Uint128::new(1)],
+            reasons: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            reward_ids: None,
+            skip_invalid: false,
+            auto_payout: None,
+            operator_tag: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.messages.into_iter().map(|m| m.msg).collect::<Vec<_>>(),
+            vec![CosmosMsg::Bank(BankMsg::Send { to_address: "user2".to_string(), amount: coins(12, DENOM) })]
+        );
+        assert_eq!(query_balance(deps.as_ref(), "user2".to_string()).unwrap(), Uint128::zero());
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::new(11));
+
+        // An unbonding delay or a withdrawal fee can't be skipped by opting into auto payout.
+        execute(deps.as_mut(), mock_env(), mock_info("user2", &[]), ExecuteMsg::SetPayoutPreference { auto: true })
+            .unwrap();
+        let msg = ExecuteMsg::SetUnbondingDelay { delay_seconds: 86400 };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), reward("user2", Some(true))).unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(query_balance(deps.as_ref(), "user2".to_string()).unwrap(), Uint128::new(10));
+
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::SetUnbondingDelay { delay_seconds: 0 }).unwrap();
+        let msg = ExecuteMsg::UpdateFeeConfig { fee_bps: 500, min_fee: None, collector: "collector".to_string() };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), info, reward("user2", Some(true))).unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(query_balance(deps.as_ref(), "user2".to_string()).unwrap(), Uint128::new(20));
+    }
+
+    fn signed_voucher(signing_key: &k256::ecdsa::SigningKey, voucher: &RewardVoucher) -> Binary {
+        use k256::ecdsa::signature::hazmat::PrehashSigner;
+
+        let hash = Sha256::digest(to_json_vec(voucher).unwrap());
+        let signature: k256::ecdsa::Signature = signing_key.sign_prehash(&hash).unwrap();
+        Binary::from(signature.to_bytes().to_vec())
+    }
+
+    #[test]
+    fn claim_voucher_credits_signed_rewards_once() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            token_denom: DENOM.to_string(),
+        };
+        let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-    let new_owner_addr = deps.api.addr_validate(&new_owner)?;
-    STATE.save(deps.storage, &State { owner: new_owner_addr.clone() })?;
+        let signing_key = k256::ecdsa::SigningKey::from_slice(&[7u8; 32]).unwrap();
+        let pubkey = signing_key.verifying_key().to_encoded_point(true).as_bytes().to_vec();
+        let msg = ExecuteMsg::SetVoucherSigner { pubkey: Some(Binary::from(pubkey)) };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-    Ok(Response::new()
-        .add_attribute("action", "update_ownership")
-        .add_attribute("new_owner", new_owner_addr.to_string()))
-}
+        let env = mock_env();
+        let voucher = RewardVoucher {
+            recipient: USER.to_string(),
+            amount: Uint128::new(25),
+            reason: "Bounty".to_string(),
+            nonce: "voucher-1".to_string(),
+            expires_at: env.block.time.plus_seconds(60),
+            contract_address: env.contract.address.to_string(),
+            chain_id: env.block.chain_id.clone(),
+        };
+        let signature = signed_voucher(&signing_key, &voucher);
+        let claim = |voucher: RewardVoucher, signature: Binary| ExecuteMsg::ClaimVoucher { voucher, signature };
 
-pub fn set_token_denom(
-    deps: DepsMut,
-    info: MessageInfo,
-    denom: String,
-) -> StdResult<Response> {
-    validate_owner(deps.as_ref(), &info)?;
-    TOKEN_DENOM.save(deps.storage, &denom)?;
+        let tampered = RewardVoucher { amount: Uint128::new(2_500), ..voucher.clone() };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("relayer", &[]), claim(tampered, signature.clone()))
+            .unwrap_err();
+        assert_eq!(err, StdError::generic_err("Invalid voucher signature"));
 
-    Ok(Response::new()
-        .add_attribute("action", "set_token_denom")
-        .add_attribute("denom", denom))
-}
+        execute(deps.as_mut(), env.clone(), mock_info("relayer", &[]), claim(voucher.clone(), signature.clone()))
+            .unwrap();
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::new(25));
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{attr, coins};
-    use cosmwasm_std::Uint128;
+        let err = execute(deps.as_mut(), env.clone(), mock_info(USER, &[]), claim(voucher.clone(), signature.clone()))
+            .unwrap_err();
+        assert_eq!(err, StdError::generic_err("Voucher nonce already used: voucher-1"));
 
-    const OWNER: &str = "owner";
-    const USER: &str = "user";
-    const DENOM: &str = "utoken";
+        let expired = RewardVoucher { nonce: "voucher-2".to_string(), expires_at: env.block.time, ..voucher.clone() };
+        let signature = signed_voucher(&signing_key, &expired);
+        let err = execute(deps.as_mut(), env.clone(), mock_info(USER, &[]), claim(expired, signature)).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Voucher expired"));
+
+        let other_chain = RewardVoucher { nonce: "voucher-3".to_string(), chain_id: "other".to_string(), ..voucher };
+        let signature = signed_voucher(&signing_key, &other_chain);
+        let err = execute(deps.as_mut(), env, mock_info(USER, &[]), claim(other_chain, signature)).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Voucher was issued for a different contract"));
+    }
 
     #[test]
-    fn proper_initialization() {
+    fn blocked_addresses_cannot_receive_rewards() {
         let mut deps = mock_dependencies();
         let msg = InstantiateMsg {
             token_denom: DENOM.to_string(),
         };
         let info = mock_info(OWNER, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-        let res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
-        assert_eq!(
-            res.attributes,
-            vec![
-                attr("action", "instantiate"),
-                attr("owner", OWNER),
-                attr("token_denom", DENOM),
-            ]
-        );
+        let user_info = mock_info(USER, &coins(100, DENOM));
+        execute(deps.as_mut(), mock_env(), user_info.clone(), ExecuteMsg::Deposit {}).unwrap();
+        let msg = ExecuteMsg::BlockAddress { address: USER.to_string(), freeze: false };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-        let state = STATE.load(&deps.storage).unwrap();
-        assert_eq!(state.owner, Addr::unchecked(OWNER));
+        let msg = reward_msg(USER, 10, "Blocked");
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Address is blocked: user"));
 
-        let token_denom = TOKEN_DENOM.load(&deps.storage).unwrap();
-        assert_eq!(token_denom, DENOM.to_string());
+        let msg = ExecuteMsg::RewardBulk {
+            recipients: vec![USER.to_string(), "user2".to_string()],
+            amounts: vec![Uint128::new(10), Uint128::new(20)],
+            reasons: vec!["a".to_string(), "b".to_string()],
+            reward_ids: None,
+            skip_invalid: true,
+            auto_payout: None,
+            operator_tag: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let response: RewardBatchResponse = cosmwasm_std::from_json(res.data.unwrap()).unwrap();
+        assert_eq!(response.results[0].status, RewardEntryStatus::Invalid);
+        assert_eq!(response.results[1].status, RewardEntryStatus::Credited);
+
+        let now = mock_env().block.time;
+        let committed = [
+            ExecuteMsg::ScheduleReward {
+                recipient: USER.to_string(),
+                amount: Uint128::new(10),
+                reason: "Blocked".to_string(),
+                release_at: now.plus_seconds(10),
+            },
+            ExecuteMsg::CreateStream {
+                recipient: USER.to_string(),
+                rate_per_second: Uint128::new(1),
+                start: now,
+                end: now.plus_seconds(10),
+                reason: "Blocked".to_string(),
+            },
+        ];
+        for msg in committed {
+            let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+            assert_eq!(err, StdError::generic_err("Address is blocked: user"));
+        }
+        let msg = ExecuteMsg::SetApprovalConfig {
+            approvers: vec!["alice".to_string()],
+            threshold: 1,
+            amount_threshold: Uint128::new(1000),
+            proposal_ttl_seconds: 3600,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let msg = ExecuteMsg::ProposeReward {
+            recipient: USER.to_string(),
+            amount: Uint128::new(5000),
+            reason: "Grant".to_string(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Address is blocked: user"));
+
+        // Blocking stops withdrawals even without a freeze.
+        let err = execute(deps.as_mut(), mock_env(), user_info, ExecuteMsg::Withdraw { amount: Uint128::new(40) })
+            .unwrap_err();
+        assert_eq!(err, StdError::generic_err("Address is blocked: user"));
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::new(100));
     }
 
     #[test]
-    fn deposit_works() {
+    fn blocked_addresses_cannot_withdraw_until_unblocked() {
         let mut deps = mock_dependencies();
         let msg = InstantiateMsg {
             token_denom: DENOM.to_string(),
@@ -307,25 +5669,29 @@ mod tests {
         let info = mock_info(OWNER, &[]);
         instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-        let deposit_info = mock_info(USER, &coins(100, DENOM));
-        let msg = ExecuteMsg::Deposit {};
-        let res = execute(deps.as_mut(), mock_env(), deposit_info.clone(), msg).unwrap();
+        let user_info = mock_info(USER, &coins(100, DENOM));
+        execute(deps.as_mut(), mock_env(), user_info.clone(), ExecuteMsg::Deposit {}).unwrap();
+        let msg = ExecuteMsg::BlockAddress { address: USER.to_string(), freeze: true };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
         assert_eq!(
-            res.attributes,
-            vec![
-                attr("action", "deposit"),
-                attr("sender", USER),
-                attr("amount", "100"),
-            ]
+            query_blocked(deps.as_ref(), None, None).unwrap(),
+            vec![BlockedAddress { address: Addr::unchecked(USER), freeze: true }]
         );
 
-        let balance = BALANCES.load(&deps.storage, &Addr::unchecked(USER)).unwrap();
-        assert_eq!(balance, Uint128::new(100));
+        let withdraw = ExecuteMsg::Withdraw { amount: Uint128::new(40) };
+        let err = execute(deps.as_mut(), mock_env(), user_info.clone(), withdraw.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Address is blocked: user"));
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::new(100));
+
+        let msg = ExecuteMsg::UnblockAddress { address: USER.to_string() };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        execute(deps.as_mut(), mock_env(), user_info, withdraw).unwrap();
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::new(60));
     }
 
     #[test]
-    fn deposit_fails_for_zero_amount() {
+    fn owner_paths_settle_committed_rewards_of_blocked_recipients() {
         let mut deps = mock_dependencies();
         let msg = InstantiateMsg {
             token_denom: DENOM.to_string(),
@@ -333,14 +5699,74 @@ mod tests {
         let info = mock_info(OWNER, &[]);
         instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-        let deposit_info = mock_info(USER, &coins(0, DENOM));
-        let msg = ExecuteMsg::Deposit {};
-        let err = execute(deps.as_mut(), mock_env(), deposit_info, msg).unwrap_err();
-        assert_eq!(err, StdError::generic_err("Deposit amount must be greater than zero"));
+        let fund_info = mock_info(OWNER, &coins(1000, DENOM));
+        execute(deps.as_mut(), mock_env(), fund_info, ExecuteMsg::FundPool {}).unwrap();
+        let now = mock_env().block.time;
+        let msg = ExecuteMsg::CreateStream {
+            recipient: USER.to_string(),
+            rate_per_second: Uint128::new(1),
+            start: now,
+            end: now.plus_seconds(100),
+            reason: "Moderation".to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::ScheduleReward {
+            recipient: USER.to_string(),
+            amount: Uint128::new(50),
+            reason: "Hackathon".to_string(),
+            release_at: now.plus_seconds(10),
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::SetApprovalConfig {
+            approvers: vec!["alice".to_string(), "bob".to_string()],
+            threshold: 2,
+            amount_threshold: Uint128::new(1000),
+            proposal_ttl_seconds: 3600,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::ProposeReward {
+            recipient: USER.to_string(),
+            amount: Uint128::new(5000),
+            reason: "Grant".to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::BlockAddress { address: USER.to_string(), freeze: false };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        assert_eq!(query_reward_pool(deps.as_ref()).unwrap(), Uint128::new(850));
+
+        let mut env = mock_env();
+        env.block.time = now.plus_seconds(30);
+
+        // Matured scheduled rewards are held and can no longer be cancelled.
+        let msg = ExecuteMsg::ReleaseScheduledRewards { address: USER.to_string() };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::zero());
+        let msg = ExecuteMsg::CancelScheduledReward { id: 1 };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Scheduled reward already released"));
+
+        // Cancelling the stream refunds what hadn't accrued and holds the rest for the recipient.
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::CancelStream { id: 1 }).unwrap();
+        assert!(res.events.iter().any(|e| e.ty == "thrive_hold_stream"));
+        assert_eq!(query_reward_pool(deps.as_ref()).unwrap(), Uint128::new(920));
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::zero());
+
+        let res = execute(deps.as_mut(), env.clone(), mock_info("bob", &[]), ExecuteMsg::ApproveReward { id: 1 })
+            .unwrap();
+        assert!(res.events.iter().any(|e| e.ty == "thrive_reject_reward"));
+        assert_eq!(PROPOSALS.load(&deps.storage, 1).unwrap().status, ProposalStatus::Rejected);
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::zero());
+
+        // Once unblocked, everything held is released to the recipient.
+        execute(deps.as_mut(), env.clone(), info, ExecuteMsg::UnblockAddress { address: USER.to_string() }).unwrap();
+        let msg = ExecuteMsg::ReleaseScheduledRewards { address: USER.to_string() };
+        execute(deps.as_mut(), env, mock_info(USER, &[]), msg).unwrap();
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::new(80));
     }
 
     #[test]
-    fn withdraw_works() {
+    fn allowlist_limits_recipients_and_accepts_merkle_proofs() {
         let mut deps = mock_dependencies();
         let msg = InstantiateMsg {
             token_denom: DENOM.to_string(),
@@ -348,31 +5774,40 @@ mod tests {
         let info = mock_info(OWNER, &[]);
         instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-        let deposit_info = mock_info(USER, &coins(200, DENOM));
-        execute(
-            deps.as_mut(),
-            mock_env(),
-            deposit_info.clone(),
-            ExecuteMsg::Deposit {},
-        )
-        .unwrap();
+        let leaf = |address: &str| -> [u8; 32] { Sha256::digest(address.as_bytes()).into() };
+        let (left, right) = (leaf("alice"), leaf("bob"));
+        let (first, second) = if left < right { (left, right) } else { (right, left) };
+        let root: [u8; 32] = Sha256::new().chain_update(first).chain_update(second).finalize().into();
 
-        let withdraw_msg = ExecuteMsg::Withdraw {
-            amount: Uint128::new(100),
+        let msg = ExecuteMsg::SetEligibilityConfig {
+            allowlist_only: true,
+            merkle_root: Some(Binary::from(root.to_vec())),
+            lifetime_cap: None,
+            period_cap: None,
+            period_seconds: 0,
         };
-        let res = execute(deps.as_mut(), mock_env(), deposit_info.clone(), withdraw_msg).unwrap();
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::AddEligible { addresses: vec![USER.to_string()] };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-        assert_eq!(
-            res.attributes,
-            vec![attr("action", "withdraw"), attr("amount", "100")]
-        );
+        let reward = |recipient: &str| reward_msg(recipient, 10, "Verified");
+        execute(deps.as_mut(), mock_env(), info.clone(), reward(USER)).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), reward("alice")).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Recipient is not eligible: alice"));
 
-        let balance = BALANCES.load(&deps.storage, &Addr::unchecked(USER)).unwrap();
-        assert_eq!(balance, Uint128::new(100));
+        let prove = |address: &str, proof: [u8; 32]| ExecuteMsg::ProveEligibility {
+            address: address.to_string(),
+            proof: vec![Binary::from(proof.to_vec())],
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("carol", &[]), prove("carol", right)).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Invalid eligibility proof"));
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), prove("alice", right)).unwrap();
+        execute(deps.as_mut(), mock_env(), info, reward("alice")).unwrap();
+        assert_eq!(query_balance(deps.as_ref(), "alice".to_string()).unwrap(), Uint128::new(10));
     }
 
     #[test]
-    fn withdraw_fails_for_zero_amount() {
+    fn reward_caps_bound_lifetime_and_period_totals() {
         let mut deps = mock_dependencies();
         let msg = InstantiateMsg {
             token_denom: DENOM.to_string(),
@@ -380,15 +5815,33 @@ mod tests {
         let info = mock_info(OWNER, &[]);
         instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-        let withdraw_msg = ExecuteMsg::Withdraw {
-            amount: Uint128::zero(),
+        let msg = ExecuteMsg::SetEligibilityConfig {
+            allowlist_only: false,
+            merkle_root: None,
+            lifetime_cap: Some(Uint128::new(100)),
+            period_cap: Some(Uint128::new(60)),
+            period_seconds: 1_000,
         };
-        let err = execute(deps.as_mut(), mock_env(), info.clone(), withdraw_msg).unwrap_err();
-        assert_eq!(err, StdError::generic_err("Withdraw amount must be greater than zero"));
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let reward = |amount: u128| reward_msg(USER, amount, "Capped");
+        execute(deps.as_mut(), mock_env(), info.clone(), reward(50)).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), reward(20)).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Period reward cap exceeded for user"));
+
+        let mut next_period = mock_env();
+        next_period.block.time = next_period.block.time.plus_seconds(1_000);
+        execute(deps.as_mut(), next_period.clone(), info.clone(), reward(40)).unwrap();
+        let usage = query_cap_usage(deps.as_ref(), next_period.clone(), USER.to_string()).unwrap();
+        assert_eq!(usage.lifetime_remaining, Some(Uint128::new(10)));
+        assert_eq!(usage.period_remaining, Some(Uint128::new(20)));
+
+        let err = execute(deps.as_mut(), next_period, info, reward(20)).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Lifetime reward cap exceeded for user"));
     }
 
     #[test]
-    fn withdraw_fails_for_insufficient_balance() {
+    fn scheduled_rewards_and_streams_reserve_caps_when_committed() {
         let mut deps = mock_dependencies();
         let msg = InstantiateMsg {
             token_denom: DENOM.to_string(),
@@ -396,15 +5849,56 @@ mod tests {
         let info = mock_info(OWNER, &[]);
         instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-        let withdraw_msg = ExecuteMsg::Withdraw {
-            amount: Uint128::new(100),
+        let fund_info = mock_info(OWNER, &coins(1000, DENOM));
+        execute(deps.as_mut(), mock_env(), fund_info, ExecuteMsg::FundPool {}).unwrap();
+        let msg = ExecuteMsg::SetEligibilityConfig {
+            allowlist_only: false,
+            merkle_root: None,
+            lifetime_cap: Some(Uint128::new(100)),
+            period_cap: Some(Uint128::new(60)),
+            period_seconds: 1_000,
         };
-        let err = execute(deps.as_mut(), mock_env(), info.clone(), withdraw_msg).unwrap_err();
-        assert_eq!(err, StdError::generic_err("Insufficient balance"));
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let now = mock_env().block.time;
+        let schedule = |amount: u128| ExecuteMsg::ScheduleReward {
+            recipient: USER.to_string(),
+            amount: Uint128::new(amount),
+            reason: "Capped".to_string(),
+            release_at: now.plus_seconds(10),
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), schedule(50)).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), schedule(20)).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Period reward cap exceeded for user"));
+
+        let mut next_period = mock_env();
+        next_period.block.time = now.plus_seconds(1_000);
+        let stream = |seconds: u64| ExecuteMsg::CreateStream {
+            recipient: USER.to_string(),
+            rate_per_second: Uint128::new(1),
+            start: now.plus_seconds(2_000),
+            end: now.plus_seconds(2_000 + seconds),
+            reason: "Capped".to_string(),
+        };
+        let err = execute(deps.as_mut(), next_period.clone(), info.clone(), stream(60)).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Lifetime reward cap exceeded for user"));
+        execute(deps.as_mut(), next_period.clone(), info.clone(), stream(40)).unwrap();
+
+        // Releasing the scheduled reward doesn't count it a second time.
+        let msg = ExecuteMsg::ReleaseScheduledRewards { address: USER.to_string() };
+        execute(deps.as_mut(), next_period.clone(), info.clone(), msg).unwrap();
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::new(50));
+        let usage = query_cap_usage(deps.as_ref(), next_period.clone(), USER.to_string()).unwrap();
+        assert_eq!(usage.lifetime_remaining, Some(Uint128::new(10)));
+
+        // Cancelling the stream gives back what it never paid out.
+        execute(deps.as_mut(), next_period.clone(), info, ExecuteMsg::CancelStream { id: 1 }).unwrap();
+        let usage = query_cap_usage(deps.as_ref(), next_period, USER.to_string()).unwrap();
+        assert_eq!(usage.lifetime_remaining, Some(Uint128::new(50)));
     }
 
     #[test]
-    fn reward_bulk_fails_for_mismatched_lengths() {
+    fn withdraw_ibc_restores_balance_on_timeout() {
         let mut deps = mock_dependencies();
         let msg = InstantiateMsg {
             token_denom: DENOM.to_string(),
@@ -412,18 +5906,67 @@ mod tests {
         let info = mock_info(OWNER, &[]);
         instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-        let msg = ExecuteMsg::RewardBulk {
-            recipients: vec![USER.to_string()],
-            amounts: vec![Uint128::new(100), Uint128::new(50)], // Mismatched length
-            reasons: vec!["Reason1".to_string()],
+        let user_info = mock_info(USER, &coins(100, DENOM));
+        execute(deps.as_mut(), mock_env(), user_info.clone(), ExecuteMsg::Deposit {}).unwrap();
+
+        let withdraw = ExecuteMsg::WithdrawIbc {
+            channel_id: "channel-0".to_string(),
+            remote_address: "osmo1remote".to_string(),
+            amount: Uint128::new(60),
+            timeout: None,
         };
+        let err = execute(deps.as_mut(), mock_env(), user_info.clone(), withdraw.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err("IBC channel not allowed: channel-0"));
 
-        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
-        assert_eq!(err, StdError::generic_err("Array lengths mismatch"));
+        let msg = ExecuteMsg::SetIbcChannel { channel_id: "channel-0".to_string(), allowed: true };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::UpdateFeeConfig { fee_bps: 1000, min_fee: None, collector: "collector".to_string() };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), user_info, withdraw).unwrap();
+        let timeout = mock_env().block.time.plus_seconds(DEFAULT_IBC_TIMEOUT_SECONDS);
+        let value = encode_ibc_transfer(
+            &mock_env().contract.address,
+            "channel-0",
+            "osmo1remote",
+            &Coin::new(54, DENOM),
+            timeout,
+        );
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::reply_always(
+                CosmosMsg::Stargate {
+                    type_url: "/ibc.applications.transfer.v1.MsgTransfer".to_string(),
+                    value: value.clone(),
+                },
+                IBC_TRANSFER_REPLY_ID,
+            )]
+        );
+        let memo = format!(r#"{{"ibc_callback":"{}"}}"#, mock_env().contract.address);
+        let memo_field = [&[0x42, memo.len() as u8][..], memo.as_bytes()].concat();
+        assert!(value.as_slice().ends_with(&memo_field));
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::new(40));
+
+        // MsgTransferResponse { sequence: 7 }
+        let result = SubMsgResult::Ok(cosmwasm_std::SubMsgResponse {
+            events: vec![],
+            data: Some(Binary::from(vec![0x08, 0x07])),
+        });
+        reply(deps.as_mut(), mock_env(), Reply { id: IBC_TRANSFER_REPLY_ID, result }).unwrap();
+        assert!(IBC_TRANSFERS.has(&deps.storage, ("channel-0", 7)));
+
+        let msg = SudoMsg::IBCLifecycleComplete(IBCLifecycleComplete::IBCTimeout {
+            channel: "channel-0".to_string(),
+            sequence: 7,
+        });
+        // The fee is only collected on acknowledgement, so a timeout restores the whole withdrawal.
+        let res = sudo(deps.as_mut(), mock_env(), msg).unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::new(100));
+        assert!(!IBC_TRANSFERS.has(&deps.storage, ("channel-0", 7)));
     }
 
     #[test]
-    fn reward_works() {
+    fn owner_settles_ibc_transfers_after_timeout() {
         let mut deps = mock_dependencies();
         let msg = InstantiateMsg {
             token_denom: DENOM.to_string(),
@@ -431,54 +5974,88 @@ mod tests {
         let info = mock_info(OWNER, &[]);
         instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-        let msg = ExecuteMsg::Reward {
-            recipient: USER.to_string(),
-            amount: Uint128::new(50),
-            reason: "Test reward".to_string(),
+        let user_info = mock_info(USER, &coins(100, DENOM));
+        execute(deps.as_mut(), mock_env(), user_info.clone(), ExecuteMsg::Deposit {}).unwrap();
+        let msg = ExecuteMsg::SetIbcChannel { channel_id: "channel-0".to_string(), allowed: true };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::UpdateFeeConfig { fee_bps: 1000, min_fee: None, collector: "collector".to_string() };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let withdraw = ExecuteMsg::WithdrawIbc {
+            channel_id: "channel-0".to_string(),
+            remote_address: "osmo1remote".to_string(),
+            amount: Uint128::new(60),
+            timeout: Some(600),
         };
+        execute(deps.as_mut(), mock_env(), user_info.clone(), withdraw).unwrap();
+        let result = SubMsgResult::Ok(cosmwasm_std::SubMsgResponse {
+            events: vec![],
+            data: Some(Binary::from(vec![0x08, 0x07])),
+        });
+        reply(deps.as_mut(), mock_env(), Reply { id: IBC_TRANSFER_REPLY_ID, result }).unwrap();
 
-        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let settle = ExecuteMsg::SettleIbcTransfer { channel_id: "channel-0".to_string(), sequence: 7, success: true };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), settle.clone()).unwrap_err();
+        assert_eq!(err, StdError::generic_err("IBC transfer has not timed out yet"));
+
+        let mut later = mock_env();
+        later.block.time = later.block.time.plus_seconds(600);
+        execute(deps.as_mut(), later.clone(), user_info, settle.clone()).unwrap_err();
+        let res = execute(deps.as_mut(), later.clone(), info.clone(), settle.clone()).unwrap();
         assert_eq!(
-            res.attributes,
-            vec![
-                attr("action", "reward"),
-                attr("recipient", USER),
-                attr("amount", "50"),
-                attr("reason", "Test reward"),
-            ]
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send { to_address: "collector".to_string(), amount: coins(6, DENOM) })]
         );
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::new(40));
+        assert!(!IBC_TRANSFERS.has(&deps.storage, ("channel-0", 7)));
 
-        let balance = BALANCES.load(&deps.storage, &Addr::unchecked(USER)).unwrap();
-        assert_eq!(balance, Uint128::new(50));
+        let err = execute(deps.as_mut(), later, info, settle).unwrap_err();
+        assert_eq!(err, StdError::generic_err("IBC transfer not found: channel-0/7"));
     }
 
     #[test]
-    fn reward_bulk_works() {
+    fn failed_withdraw_payout_is_credited_back() {
         let mut deps = mock_dependencies();
         let msg = InstantiateMsg {
             token_denom: DENOM.to_string(),
         };
         let info = mock_info(OWNER, &[]);
         instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
-    
-        let msg = ExecuteMsg::RewardBulk {
-            recipients: vec![USER.to_string(), "user2".to_string()],
-            amounts: vec![Uint128::new(100), Uint128::new(50)],
-            reasons: vec!["Reason1".to_string(), "Reason2".to_string()],
-        };
-    
-        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
-        assert_eq!(res.attributes, vec![attr("action", "reward_bulk")]);
-    
-        let balance1 = BALANCES.load(&deps.storage, &Addr::unchecked(USER)).unwrap();
-        assert_eq!(balance1, Uint128::new(100));
-    
-        let balance2 = BALANCES.load(&deps.storage, &Addr::unchecked("user2")).unwrap();
-        assert_eq!(balance2, Uint128::new(50));
+
+        let msg = ExecuteMsg::UpdateFeeConfig { fee_bps: 1000, min_fee: None, collector: "collector".to_string() };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let user_info = mock_info(USER, &coins(100, DENOM));
+        execute(deps.as_mut(), mock_env(), user_info.clone(), ExecuteMsg::Deposit {}).unwrap();
+
+        let withdraw = ExecuteMsg::Withdraw { amount: Uint128::new(100) };
+        let res = execute(deps.as_mut(), mock_env(), user_info, withdraw).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::reply_always(
+                BankMsg::Send { to_address: USER.to_string(), amount: coins(90, DENOM) },
+                WITHDRAW_PAYOUT_REPLY_ID,
+            )]
+        );
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::zero());
+
+        // The fee is not collected and the whole withdrawal is credited back.
+        let result = SubMsgResult::Err("insufficient funds".to_string());
+        let res = reply(deps.as_mut(), mock_env(), Reply { id: WITHDRAW_PAYOUT_REPLY_ID, result }).unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(
+            res.events,
+            vec![Event::new("thrive_withdraw_failed")
+                .add_attribute("recipient", USER)
+                .add_attribute("amount", "100")
+                .add_attribute("error", "insufficient funds")
+                .add_attribute("balance", "100")]
+        );
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::new(100));
+        assert_eq!(STATS.load(&deps.storage).unwrap().total_withdrawn, Uint128::zero());
+        assert!(PENDING_PAYOUT.may_load(&deps.storage).unwrap().is_none());
     }
 
     #[test]
-    fn query_balance_works() {
+    fn first_reward_issues_fee_grant_within_budget() {
         let mut deps = mock_dependencies();
         let msg = InstantiateMsg {
             token_denom: DENOM.to_string(),
@@ -486,21 +6063,68 @@ mod tests {
         let info = mock_info(OWNER, &[]);
         instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-        let deposit_info = mock_info(USER, &coins(100, DENOM));
-        execute(
-            deps.as_mut(),
-            mock_env(),
-            deposit_info.clone(),
-            ExecuteMsg::Deposit {},
-        )
-        .unwrap();
+        let err = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &coins(1500, "uxion")), ExecuteMsg::FundFeeGrants {})
+            .unwrap_err();
+        assert_eq!(err, StdError::generic_err("Fee grant config not set"));
+        let msg = ExecuteMsg::SetFeeGrantConfig {
+            denom: "uxion".to_string(),
+            spend_limit: Uint128::new(1000),
+            expiration_seconds: None,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        assert_eq!(FEE_GRANT_CONFIG.load(&deps.storage).unwrap().budget, Uint128::zero());
 
-        let balance = query_balance(deps.as_ref(), USER.to_string()).unwrap();
-        assert_eq!(balance, Uint128::new(100));
+        // Funds in other denoms don't count towards the budget.
+        let funds = [coins(1500, "uxion"), coins(100, DENOM)].concat();
+        execute(deps.as_mut(), mock_env(), mock_info("sponsor", &funds), ExecuteMsg::FundFeeGrants {}).unwrap();
+        assert_eq!(FEE_GRANT_CONFIG.load(&deps.storage).unwrap().budget, Uint128::new(1500));
+        assert_eq!(query_reward_pool(deps.as_ref()).unwrap(), Uint128::zero());
+
+        let reward = |recipient: &str| reward_msg(recipient, 10, "Gasless");
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), reward(USER)).unwrap();
+
+        let coin = [&[0x0a, 5][..], b"uxion", &[0x12, 4], b"1000"].concat();
+        let allowance = [&[0x0a, coin.len() as u8][..], &coin].concat();
+        let type_url = b"/cosmos.feegrant.v1beta1.BasicAllowance";
+        let any = [&[0x0a, type_url.len() as u8][..], type_url, &[0x12, allowance.len() as u8], &allowance].concat();
+        let granter = mock_env().contract.address;
+        let expected = [
+            &[0x0a, granter.as_str().len() as u8][..], granter.as_bytes(),
+            &[0x12, 4], b"user",
+            &[0x1a, any.len() as u8], &any,
+        ].concat();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::reply_always(
+                CosmosMsg::Stargate {
+                    type_url: "/cosmos.feegrant.v1beta1.MsgGrantAllowance".to_string(),
+                    value: Binary::from(expected),
+                },
+                FEE_GRANT_REPLY_ID_OFFSET + 1,
+            )]
+        );
+
+        // Already granted addresses and grants beyond the budget are skipped.
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), reward(USER)).unwrap();
+        assert!(res.messages.is_empty());
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), reward("user2")).unwrap();
+        assert!(res.messages.is_empty());
+
+        // Only an address's first reward issues a grant, even once the budget is topped up.
+        execute(deps.as_mut(), mock_env(), mock_info(OWNER, &coins(1000, "uxion")), ExecuteMsg::FundFeeGrants {}).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), reward("user2")).unwrap();
+        assert!(res.messages.is_empty());
+        let res = execute(deps.as_mut(), mock_env(), info, reward("user3")).unwrap();
+        assert_eq!(res.messages.len(), 1);
+
+        let result = SubMsgResult::Err("fee allowance already exists".to_string());
+        reply(deps.as_mut(), mock_env(), Reply { id: FEE_GRANT_REPLY_ID_OFFSET + 1, result }).unwrap();
+        assert!(!FEE_GRANTS.has(&deps.storage, &Addr::unchecked(USER)));
+        assert_eq!(FEE_GRANT_CONFIG.load(&deps.storage).unwrap().budget, Uint128::new(1500));
     }
 
     #[test]
-    fn update_ownership_works() {
+    fn operator_tags_are_validated_and_recorded() {
         let mut deps = mock_dependencies();
         let msg = InstantiateMsg {
             token_denom: DENOM.to_string(),
@@ -508,26 +6132,52 @@ mod tests {
         let info = mock_info(OWNER, &[]);
         instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-        let new_owner = "new_owner";
-        let msg = ExecuteMsg::UpdateOwnership {
-            new_owner: new_owner.to_string(),
+        let reward = |operator_tag: &str| {
+            reward_entry_msg(reward_entry(USER, 10, "Tagged"), Some(operator_tag.to_string()))
         };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), reward("backend-1")).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Unknown operator tag: backend-1"));
 
-        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::RegisterOperatorTag { tag: "backend-1".to_string(), grantee: "operator".to_string() };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // Neither the owner nor another delegate may use the operator's tag.
+        let delegate = |address: &str| ExecuteMsg::SetDelegate {
+            address: address.to_string(),
+            quota: Uint128::new(100),
+            period_seconds: 86400,
+            expires_at: None,
+            reason_prefix: None,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), delegate("lead")).unwrap();
+        execute(deps.as_mut(), mock_env(), info.clone(), delegate("operator")).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), mock_info("lead", &[]), reward("backend-1")).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Unauthorized to use operator tag: backend-1"));
+        let err = execute(deps.as_mut(), mock_env(), info, reward("backend-1")).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Unauthorized to use operator tag: backend-1"));
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("operator", &[]), reward("backend-1")).unwrap();
+
+        let event = res.events.iter().find(|e| e.ty == "thrive_reward").unwrap();
+        assert!(event.attributes.contains(&attr("operator_tag", "backend-1")));
         assert_eq!(
-            res.attributes,
-            vec![
-                attr("action", "update_ownership"),
-                attr("new_owner", new_owner),
-            ]
+            query_rewards(deps.as_ref(), None, None).unwrap(),
+            vec![RewardRecord {
+                id: 1,
+                recipient: Addr::unchecked(USER),
+                amount: Uint128::new(10),
+                reason: "Tagged".to_string(),
+                campaign_id: None,
+                operator_tag: Some("backend-1".to_string()),
+                reason_code: None,
+                memo: None,
+                timestamp: mock_env().block.time,
+            }]
         );
-
-        let state = STATE.load(&deps.storage).unwrap();
-        assert_eq!(state.owner, Addr::unchecked(new_owner));
     }
 
     #[test]
-    fn set_token_denom_works() {
+    fn reason_codes_default_cap_and_total_rewards() {
         let mut deps = mock_dependencies();
         let msg = InstantiateMsg {
             token_denom: DENOM.to_string(),
@@ -535,22 +6185,53 @@ mod tests {
         let info = mock_info(OWNER, &[]);
         instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-        let new_denom = "utest";
-        let res = set_token_denom(deps.as_mut(), info.clone(), new_denom.to_string()).unwrap();
-        assert_eq!(
-            res.attributes,
-            vec![
-                attr("action", "set_token_denom"),
-                attr("denom", new_denom),
-            ]
-        );
+        let reward = |amount: u128, memo: Option<String>| {
+            let entry = reward_entry(USER, amount, "Bug report");
+            reward_entry_msg(RewardEntry { reason_code: Some("bug".to_string()), memo, ..entry }, None)
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), reward(10, None)).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Unknown reason code: bug"));
 
-        let token_denom = TOKEN_DENOM.load(&deps.storage).unwrap();
-        assert_eq!(token_denom, new_denom);
+        let msg = ExecuteMsg::RegisterReasonCode {
+            code: "bug".to_string(),
+            description: "Bug report".to_string(),
+            default_amount: Some(Uint128::new(500)),
+            max_amount: Some(Uint128::new(100)),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Default amount cannot exceed max amount"));
+        let msg = ExecuteMsg::RegisterReasonCode {
+            code: "bug".to_string(),
+            description: "Bug report".to_string(),
+            default_amount: Some(Uint128::new(50)),
+            max_amount: Some(Uint128::new(100)),
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // A zero amount pays the code's default.
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), reward(0, Some("PR #12".to_string()))).unwrap();
+        let event = res.events.iter().find(|e| e.ty == "thrive_reward").unwrap();
+        assert!(event.attributes.contains(&attr("amount", "50")));
+        assert!(event.attributes.contains(&attr("reason_code", "bug")));
+        assert!(event.attributes.contains(&attr("memo", "PR #12")));
+        execute(deps.as_mut(), mock_env(), info.clone(), reward(100, None)).unwrap();
+
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), reward(101, None)).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Reward amount exceeds maximum for reason code bug"));
+        let err = execute(deps.as_mut(), mock_env(), info, reward(10, Some("x".repeat(MAX_REASON_LENGTH + 1))))
+            .unwrap_err();
+        assert_eq!(err, StdError::generic_err("Memo exceeds maximum length of 256"));
+
+        let codes = query_reason_codes(deps.as_ref(), None, None).unwrap();
+        assert_eq!(codes.len(), 1);
+        assert_eq!(codes[0].totals, ReasonCodeTotals { count: 2, amount: Uint128::new(150) });
+        let record = REWARD_HISTORY.load(&deps.storage, 1).unwrap();
+        assert_eq!(record.reason_code, Some("bug".to_string()));
+        assert_eq!(record.memo, Some("PR #12".to_string()));
     }
 
     #[test]
-    fn set_token_denom_fails_for_unauthorized() {
+    fn stats_track_rewards_deposits_and_withdrawals() {
         let mut deps = mock_dependencies();
         let msg = InstantiateMsg {
             token_denom: DENOM.to_string(),
@@ -558,14 +6239,41 @@ mod tests {
         let info = mock_info(OWNER, &[]);
         instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-        let unauthorized_info = mock_info(USER, &[]);
-        let new_denom = "utest";
-        let err = set_token_denom(deps.as_mut(), unauthorized_info, new_denom.to_string()).unwrap_err();
-        assert_eq!(err, StdError::generic_err("Unauthorized: Only the owner can call this"));
+        execute(deps.as_mut(), mock_env(), info.clone(), reward_msg(USER, 100, "Docs")).unwrap();
+        execute(deps.as_mut(), mock_env(), info.clone(), reward_msg(USER, 50, "Bug fix")).unwrap();
+        execute(deps.as_mut(), mock_env(), info, reward_msg("user2", 25, "Docs")).unwrap();
+
+        let user_info = mock_info(USER, &coins(30, DENOM));
+        execute(deps.as_mut(), mock_env(), user_info.clone(), ExecuteMsg::Deposit {}).unwrap();
+        let withdraw = ExecuteMsg::Withdraw { amount: Uint128::new(80) };
+        execute(deps.as_mut(), mock_env(), user_info.clone(), withdraw).unwrap();
+        let withdraw = ExecuteMsg::Withdraw { amount: Uint128::new(20) };
+        execute(deps.as_mut(), mock_env(), user_info, withdraw).unwrap();
+        let result = SubMsgResult::Err("insufficient funds".to_string());
+        reply(deps.as_mut(), mock_env(), Reply { id: WITHDRAW_PAYOUT_REPLY_ID, result }).unwrap();
+
+        let stats: Stats = cosmwasm_std::from_json(query(deps.as_ref(), mock_env(), QueryMsg::GetStats {}).unwrap()).unwrap();
+        assert_eq!(stats, Stats {
+            total_rewarded: Uint128::new(175),
+            reward_count: 3,
+            unique_recipients: 2,
+            total_deposited: Uint128::new(30),
+            total_withdrawn: Uint128::new(80),
+        });
+        assert_eq!(query_account_stats(deps.as_ref(), USER.to_string()).unwrap(), AccountStats {
+            total_received: Uint128::new(150),
+            reward_count: 2,
+            total_deposited: Uint128::new(30),
+            total_withdrawn: Uint128::new(80),
+        });
+        assert_eq!(query_reason_totals(deps.as_ref(), None, None).unwrap(), vec![
+            ReasonTotalResponse { reason: "Bug fix".to_string(), amount: Uint128::new(50) },
+            ReasonTotalResponse { reason: "Docs".to_string(), amount: Uint128::new(125) },
+        ]);
     }
 
     #[test]
-    fn query_token_denom_works() {
+    fn stats_overflow_leaves_skipped_entry_uncredited() {
         let mut deps = mock_dependencies();
         let msg = InstantiateMsg {
             token_denom: DENOM.to_string(),
@@ -573,12 +6281,28 @@ mod tests {
         let info = mock_info(OWNER, &[]);
         instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-        let token_denom = query_token_denom(deps.as_ref()).unwrap();
-        assert_eq!(token_denom, DENOM);
+        let stats = Stats { total_rewarded: Uint128::MAX, ..Stats::default() };
+        STATS.save(&mut deps.storage, &stats).unwrap();
+
+        let msg = ExecuteMsg::RewardBulk {
+            recipients: vec![USER.to_string()],
+            amounts: vec![Uint128::new(7)],
+            reasons: vec!["Reason1".to_string()],
+            reward_ids: None,
+            skip_invalid: true,
+            auto_payout: None,
+            operator_tag: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let response: RewardBatchResponse = cosmwasm_std::from_json(res.data.unwrap()).unwrap();
+        assert_eq!(response.results[0].status, RewardEntryStatus::Invalid);
+        assert_eq!(query_balance(deps.as_ref(), USER.to_string()).unwrap(), Uint128::zero());
+        assert_eq!(STATS.load(&deps.storage).unwrap(), stats);
+        assert!(query_top_recipients(deps.as_ref(), None, None, None).unwrap().is_empty());
     }
 
     #[test]
-    fn validate_owner_works() {
+    fn top_recipients_are_ranked_per_campaign_and_paginated() {
         let mut deps = mock_dependencies();
         let msg = InstantiateMsg {
             token_denom: DENOM.to_string(),
@@ -586,8 +6310,38 @@ mod tests {
         let info = mock_info(OWNER, &[]);
         instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-        let unauthorized_info = mock_info(USER, &[]);
-        let err = validate_owner(deps.as_ref(), &unauthorized_info).unwrap_err();
-        assert_eq!(err, StdError::generic_err("Unauthorized: Only the owner can call this"));
+        let now = mock_env().block.time;
+        let msg = ExecuteMsg::CreateCampaign {
+            name: "Gitcoin round 4".to_string(),
+            budget: Uint128::new(1000),
+            denom: DENOM.to_string(),
+            start: now,
+            end: now.plus_seconds(86400),
+            managers: vec![],
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let reward = |recipient: &str, amount: u128, campaign_id: Option<u64>| {
+            reward_entry_msg(RewardEntry { campaign_id, ..reward_entry(recipient, amount, "Contribution") }, None)
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), reward("alice", 30, None)).unwrap();
+        execute(deps.as_mut(), mock_env(), info.clone(), reward("bob", 20, Some(1))).unwrap();
+        execute(deps.as_mut(), mock_env(), info.clone(), reward("carol", 25, Some(1))).unwrap();
+        execute(deps.as_mut(), mock_env(), info, reward("bob", 15, None)).unwrap();
+
+        let ranking = |totals: Vec<RecipientTotal>| -> Vec<(String, u128)> {
+            totals.into_iter().map(|total| (total.recipient.to_string(), total.amount.u128())).collect()
+        };
+        let top = query_top_recipients(deps.as_ref(), None, None, None).unwrap();
+        assert_eq!(ranking(top), vec![
+            ("bob".to_string(), 35),
+            ("alice".to_string(), 30),
+            ("carol".to_string(), 25),
+        ]);
+        let page = query_top_recipients(deps.as_ref(), Some(1), Some(0), Some("bob".to_string())).unwrap();
+        assert_eq!(ranking(page), vec![("alice".to_string(), 30)]);
+
+        let top = query_top_recipients(deps.as_ref(), None, Some(1), None).unwrap();
+        assert_eq!(ranking(top), vec![("carol".to_string(), 25), ("bob".to_string(), 20)]);
     }
 }